@@ -0,0 +1,47 @@
+use std::{env, path::PathBuf, str::FromStr};
+
+use crate::error::BenchError;
+
+/// Bench settings, read from the environment. Paths default to locations
+/// relative to the `anchor` directory:
+///
+/// - `BENCH_PROGRAM`: the built program, defaults to `target/deploy/counter.so`
+/// - `BENCH_METADATA_PROGRAM`: the Metaplex token metadata program, defaults to
+///   `bench/fixtures/mpl_token_metadata.so`, dumped with
+///   `solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s bench/fixtures/mpl_token_metadata.so`
+/// - `BENCH_BASELINE`: report to compare against, defaults to `bench/baseline.json`
+/// - `BENCH_THRESHOLD_PERCENT`: growth in compute units and rent a flow may have, defaults to 5
+/// - `BENCH_UPDATE_BASELINE`: write the report as the new baseline instead of comparing, defaults to `false`
+pub struct Config {
+    pub program: PathBuf,
+    pub metadata_program: PathBuf,
+    pub baseline: PathBuf,
+    pub threshold_percent: u64,
+    pub update_baseline: bool,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, BenchError> {
+        let anchor_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+
+        Ok(Self {
+            program: optional("BENCH_PROGRAM", anchor_dir.join("target/deploy/counter.so"))?,
+            metadata_program: optional(
+                "BENCH_METADATA_PROGRAM",
+                anchor_dir.join("bench/fixtures/mpl_token_metadata.so"),
+            )?,
+            baseline: optional("BENCH_BASELINE", anchor_dir.join("bench/baseline.json"))?,
+            threshold_percent: optional("BENCH_THRESHOLD_PERCENT", 5)?,
+            update_baseline: optional("BENCH_UPDATE_BASELINE", false)?,
+        })
+    }
+}
+
+fn optional<T: FromStr>(name: &str, default: T) -> Result<T, BenchError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| BenchError::Config(format!("invalid value for {name}"))),
+        Err(_) => Ok(default),
+    }
+}
@@ -0,0 +1,17 @@
+#[derive(Debug, thiserror::Error)]
+pub enum BenchError {
+    #[error("invalid config: {0}")]
+    Config(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid report: {0}")]
+    Report(#[from] serde_json::Error),
+    #[error("setup failed: {0}")]
+    Setup(String),
+    #[error("transaction failed: {error}\n{}", logs.join("\n"))]
+    Transaction { error: String, logs: Vec<String> },
+    #[error("no baseline at {0}, run with BENCH_UPDATE_BASELINE=true to record one")]
+    MissingBaseline(String),
+    #[error("{0} differences to the baseline, record a new one with BENCH_UPDATE_BASELINE=true if they are intended")]
+    Regression(usize),
+}
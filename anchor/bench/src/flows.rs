@@ -0,0 +1,918 @@
+use std::path::Path;
+
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_spl::{associated_token, token::spl_token};
+use counter::{
+    ClaimCode, ClaimCodes, QuestionKind, RegistrationAnswer, RegistrationQuestion,
+    CLEANUP_GRACE_PERIOD,
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+use crate::{
+    error::BenchError,
+    harness::Harness,
+    instructions::{self as ix, EventKeys, PoolKeys, SeriesKeys, ATTENTANCE_CODE},
+    report::Report,
+};
+
+/// Clock at the start of the run, fixed so reports of different runs compare.
+const START: i64 = 1_700_000_000;
+const EVENT_START: i64 = START - 60;
+const EVENT_END: i64 = START + 3_600;
+/// Deadline for deposit claims and sponsor rewards.
+const CLAIM_DEADLINE: i64 = START + 7_200;
+
+const FREE_TIER: u8 = 0;
+const PAID_TIER: u8 = 1;
+const TICKET_PRICE: u64 = LAMPORTS_PER_SOL / 10;
+const DEPOSIT_LAMPORTS: u64 = 1_000_000;
+
+/// Compute unit limit clients request for transactions calling the token
+/// metadata program, the default of 200k is not enough for a badge.
+const MINT_COMPUTE_UNITS: u32 = 400_000;
+
+const CLAIM_CODES: u32 = 1_024;
+/// The rotating code event starts after setup, rotation is locked once it runs.
+const ROTATING_START: i64 = START + 60;
+const CODE_ROTATION_PERIOD: u32 = 60;
+const CODE_CHAIN_LENGTH: u32 = 1_000;
+const GEOHASH: &[u8; counter::ATTESTED_GEOHASH_LENGTH] = b"u4pruy";
+const SPONSOR_TOKENS: u64 = 1_000_000_000;
+
+/// Runs every flow against the built program and returns what each step
+/// cost. Every instruction runs at least once, in the order an event goes
+/// through them: setup before it starts, attendance while it runs, claims once
+/// it ended and cleanup after the grace period.
+pub fn run(program: &Path, metadata_program: &Path) -> Result<Report, BenchError> {
+    let admin = Keypair::new();
+    let mut harness = Harness::new(program, metadata_program, &admin.pubkey())?;
+    harness.warp_to(START);
+
+    let mut run = Run {
+        harness,
+        admin,
+        organizer: Keypair::new(),
+        treasury: Pubkey::new_unique(),
+    };
+    run.harness
+        .airdrop(&run.admin.pubkey(), 10 * LAMPORTS_PER_SOL)?;
+    run.harness
+        .airdrop(&run.organizer.pubkey(), 1_000 * LAMPORTS_PER_SOL)?;
+
+    let mut fixture = run.before_event()?;
+
+    run.harness.warp_to(START + 120);
+    run.during_event(&mut fixture)?;
+
+    run.harness.warp_to(EVENT_END + 1);
+    run.after_event(&fixture)?;
+
+    run.harness.warp_to(CLAIM_DEADLINE + 1);
+    run.after_claim_deadline(&fixture)?;
+
+    run.harness.warp_to(EVENT_END + CLEANUP_GRACE_PERIOD + 1);
+    run.after_grace_period(&fixture)?;
+
+    Ok(run.harness.finish())
+}
+
+struct Run {
+    harness: Harness,
+    admin: Keypair,
+    organizer: Keypair,
+    treasury: Pubkey,
+}
+
+/// Events and wallets shared between the phases of a run.
+struct Fixture {
+    /// Free and paid tier, a deposit and a sponsor pool.
+    main: EventKeys,
+    sessions: EventKeys,
+    claim_codes: EventKeys,
+    walk_in: EventKeys,
+    geofenced: EventKeys,
+    rotating: EventKeys,
+    questions: EventKeys,
+    /// Capacity of one, for the waitlist.
+    small: EventKeys,
+    series: SeriesKeys,
+    occurrence: EventKeys,
+    sponsor: Keypair,
+    pool: PoolKeys,
+    /// Checks in to `main`, then claims the deposit and a sponsor reward.
+    attentee: Keypair,
+    /// Everyone else still registered for `main` once it ends, cranked closed
+    /// before the organizer closes it.
+    main_attentees: Vec<Keypair>,
+    /// Registers for `geofenced` and is cleaned up after the grace period.
+    geofenced_attentee: Keypair,
+    attestation_authority: Keypair,
+    code_seed: [u8; 32],
+}
+
+impl Run {
+    fn before_event(&mut self) -> Result<Fixture, BenchError> {
+        let organizer = self.organizer.pubkey();
+
+        let flow = "admin: initialize and update config";
+        self.measure(
+            flow,
+            &[ix::initialize_config(&self.admin.pubkey(), self.treasury)],
+            Role::Admin,
+        )?;
+        self.measure(
+            flow,
+            &[ix::update_config(&self.admin.pubkey(), self.treasury)],
+            Role::Admin,
+        )?;
+
+        let main = EventKeys::new(organizer, "bench-main");
+        let flow = "organizer: create event with tiers and deposit";
+        self.measure(
+            flow,
+            &with_mint_budget(ix::initialize_event(&main, EVENT_START, EVENT_END, 100)),
+            Role::Organizer,
+        )?;
+        self.measure(
+            flow,
+            &[ix::create_ticket_tier(
+                &main,
+                FREE_TIER,
+                0,
+                EVENT_START,
+                EVENT_END,
+            )],
+            Role::Organizer,
+        )?;
+        self.measure(
+            flow,
+            &[ix::create_ticket_tier(
+                &main,
+                PAID_TIER,
+                TICKET_PRICE,
+                EVENT_START,
+                EVENT_END,
+            )],
+            Role::Organizer,
+        )?;
+        self.measure(
+            flow,
+            &[ix::set_event_deposit(
+                &main,
+                DEPOSIT_LAMPORTS,
+                CLAIM_DEADLINE,
+            )],
+            Role::Organizer,
+        )?;
+
+        let sponsor = self.wallet()?;
+        let mint = self.create_token_mint(&sponsor)?;
+        let pool = PoolKeys::new(&main, sponsor.pubkey(), mint);
+        let sponsor_tokens =
+            associated_token::get_associated_token_address(&sponsor.pubkey(), &mint);
+        let flow = "sponsor: create and fund pool";
+        self.harness.measure(
+            flow,
+            &[ix::create_sponsor_pool(&main, &pool, 0, CLAIM_DEADLINE)],
+            &[&sponsor],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::fund_sponsor_pool(
+                &main,
+                &pool,
+                sponsor_tokens,
+                SPONSOR_TOKENS,
+            )],
+            &[&sponsor],
+        )?;
+
+        let sessions = self.create_event("bench-sessions", EVENT_START, EVENT_END, 100)?;
+        let flow = "organizer: add sessions";
+        for session_id in 0..2 {
+            self.measure(
+                flow,
+                &[ix::create_session(
+                    &sessions,
+                    session_id,
+                    EVENT_START,
+                    EVENT_END,
+                )],
+                Role::Organizer,
+            )?;
+        }
+        self.measure(
+            flow,
+            &[ix::set_badge_requirement(&sessions, 2)],
+            Role::Organizer,
+        )?;
+
+        let claim_codes = self.create_event("bench-claim-codes", EVENT_START, EVENT_END, 100)?;
+        let (root, _) = claim_code_proof(&codes(), 0);
+        self.measure(
+            "organizer: issue claim codes",
+            &[ix::set_claim_codes(&claim_codes, root, CLAIM_CODES)],
+            Role::Organizer,
+        )?;
+
+        let walk_in = self.create_event("bench-walk-in", EVENT_START, EVENT_END, 100)?;
+        self.measure(
+            "organizer: open walk-ins",
+            &[ix::set_walk_in_tier(&walk_in, Some(FREE_TIER))],
+            Role::Organizer,
+        )?;
+
+        let geofenced = self.create_event("bench-geofenced", EVENT_START, EVENT_END, 100)?;
+        let attestation_authority = Keypair::new();
+        self.measure(
+            "organizer: set geofence",
+            &[ix::set_geofence(
+                &geofenced,
+                "u4pru",
+                attestation_authority.pubkey(),
+            )],
+            Role::Organizer,
+        )?;
+
+        let rotating = self.create_event("bench-rotating", ROTATING_START, EVENT_END, 100)?;
+        let code_seed = [9; 32];
+        self.measure(
+            "organizer: rotate attendance codes",
+            &[ix::set_code_rotation(
+                &rotating,
+                hash_chain(code_seed, CODE_CHAIN_LENGTH),
+                CODE_ROTATION_PERIOD,
+            )],
+            Role::Organizer,
+        )?;
+
+        let questions = self.create_event("bench-questions", EVENT_START, EVENT_END, 100)?;
+        self.measure(
+            "organizer: ask registration questions",
+            &[ix::set_registration_questions(
+                &questions,
+                vec![
+                    RegistrationQuestion {
+                        kind: QuestionKind::Choice,
+                        prompt: "T-shirt size: S, M or L".to_string(),
+                        choices: 3,
+                        required: true,
+                    },
+                    RegistrationQuestion {
+                        kind: QuestionKind::Text,
+                        prompt: "Dietary requirements".to_string(),
+                        choices: 0,
+                        required: false,
+                    },
+                ],
+            )],
+            Role::Organizer,
+        )?;
+
+        let small = self.create_event("bench-small", EVENT_START, EVENT_END, 1)?;
+
+        let series = SeriesKeys::new(organizer, "bench-series");
+        let occurrence = EventKeys::occurrence(&series, "bench-series-1");
+        let flow = "organizer: create series and occurrence";
+        self.measure(
+            flow,
+            &with_mint_budget(ix::initialize_series(&series)),
+            Role::Organizer,
+        )?;
+        self.measure(
+            flow,
+            &[ix::create_series_occurrence(
+                &series,
+                &occurrence,
+                EVENT_START,
+                EVENT_END,
+                100,
+            )],
+            Role::Organizer,
+        )?;
+        self.measure(
+            flow,
+            &[ix::create_ticket_tier(
+                &occurrence,
+                FREE_TIER,
+                0,
+                EVENT_START,
+                EVENT_END,
+            )],
+            Role::Organizer,
+        )?;
+
+        Ok(Fixture {
+            main,
+            sessions,
+            claim_codes,
+            walk_in,
+            geofenced,
+            rotating,
+            questions,
+            small,
+            series,
+            occurrence,
+            sponsor,
+            pool,
+            attentee: self.wallet()?,
+            main_attentees: Vec::new(),
+            geofenced_attentee: self.wallet()?,
+            attestation_authority,
+            code_seed,
+        })
+    }
+
+    fn during_event(&mut self, fixture: &mut Fixture) -> Result<(), BenchError> {
+        let main = &fixture.main;
+
+        let attentee = &fixture.attentee;
+        let flow = "attentee: register, check in and mint";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                main,
+                &attentee.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[attentee],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::check_in(main, &attentee.pubkey(), ATTENTANCE_CODE)],
+            &[attentee],
+        )?;
+        self.harness.measure(
+            flow,
+            &with_mint_budget(ix::mint_nft(main, &attentee.pubkey(), FREE_TIER, None)),
+            &[attentee],
+        )?;
+
+        let buyer = self.wallet()?;
+        self.harness.measure(
+            "attentee: buy a ticket",
+            &[ix::register_event(
+                main,
+                &buyer.pubkey(),
+                PAID_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&buyer],
+        )?;
+        // cancelling after the start forfeits the deposit to the organizer
+        self.harness.measure(
+            "attentee: cancel registration",
+            &[ix::cancel_registration(main, &buyer.pubkey(), PAID_TIER)],
+            &[&buyer],
+        )?;
+
+        let respondent = self.wallet()?;
+        self.harness.measure(
+            "attentee: register with answers",
+            &[ix::register_event(
+                &fixture.questions,
+                &respondent.pubkey(),
+                FREE_TIER,
+                vec![
+                    RegistrationAnswer::Choice(1),
+                    RegistrationAnswer::TextHash(hash(b"none").to_bytes()),
+                ],
+                self.treasury,
+            )],
+            &[&respondent],
+        )?;
+
+        let session_attentee = self.wallet()?;
+        let flow = "attentee: attend sessions and mint";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                &fixture.sessions,
+                &session_attentee.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&session_attentee],
+        )?;
+        for session_id in 0..2 {
+            self.harness.measure(
+                flow,
+                &[ix::check_in_session(
+                    &fixture.sessions,
+                    &session_attentee.pubkey(),
+                    session_id,
+                )],
+                &[&session_attentee],
+            )?;
+        }
+        self.harness.measure(
+            flow,
+            &with_mint_budget(ix::mint_nft(
+                &fixture.sessions,
+                &session_attentee.pubkey(),
+                FREE_TIER,
+                None,
+            )),
+            &[&session_attentee],
+        )?;
+
+        let claimer = self.wallet()?;
+        let (_, proof) = claim_code_proof(&codes(), 0);
+        let flow = "attentee: mint with a claim code";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                &fixture.claim_codes,
+                &claimer.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&claimer],
+        )?;
+        self.harness.measure(
+            flow,
+            &with_mint_budget(ix::mint_nft(
+                &fixture.claim_codes,
+                &claimer.pubkey(),
+                FREE_TIER,
+                Some(ClaimCode {
+                    index: 0,
+                    code: codes()[0],
+                    proof,
+                }),
+            )),
+            &[&claimer],
+        )?;
+
+        let walk_in = self.wallet()?;
+        self.harness.measure(
+            "attentee: walk in and mint",
+            &with_mint_budget(ix::walk_in_mint(
+                &fixture.walk_in,
+                &walk_in.pubkey(),
+                FREE_TIER,
+            )),
+            &[&walk_in],
+        )?;
+
+        let located = &fixture.geofenced_attentee;
+        let flow = "attentee: check in with a location attestation";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                &fixture.geofenced,
+                &located.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[located],
+        )?;
+        self.harness.measure(
+            flow,
+            &[
+                ix::location_attestation(
+                    &fixture.attestation_authority,
+                    &located.pubkey(),
+                    &fixture.geofenced,
+                    GEOHASH,
+                    self.harness.now(),
+                ),
+                ix::check_in_with_attestation(&fixture.geofenced, &located.pubkey()),
+            ],
+            &[located],
+        )?;
+
+        // the code of window `n` hashes to the commitment in `n` steps
+        let rotating = self.wallet()?;
+        let window =
+            ((self.harness.now() - ROTATING_START) / CODE_ROTATION_PERIOD as i64) as u32 + 1;
+        let flow = "attentee: check in with a rotating code";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                &fixture.rotating,
+                &rotating.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&rotating],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::check_in(
+                &fixture.rotating,
+                &rotating.pubkey(),
+                hash_chain(fixture.code_seed, CODE_CHAIN_LENGTH - window),
+            )],
+            &[&rotating],
+        )?;
+
+        let first = self.wallet()?;
+        self.register(&fixture.small, &first)?;
+        let waiting = self.wallet()?;
+        let flow = "attentee: join and leave the waitlist";
+        self.harness.measure(
+            flow,
+            &[ix::join_waitlist(&fixture.small, &waiting.pubkey())],
+            &[&waiting],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::leave_waitlist(&fixture.small, &waiting.pubkey())],
+            &[&waiting],
+        )?;
+
+        let flow = "attentee: update and close profile";
+        self.harness
+            .measure(flow, &[ix::update_profile(&waiting.pubkey())], &[&waiting])?;
+        self.harness
+            .measure(flow, &[ix::close_profile(&waiting.pubkey())], &[&waiting])?;
+
+        let recipients = [self.wallet()?, self.wallet()?, self.wallet()?];
+        for recipient in &recipients {
+            self.register(main, recipient)?;
+        }
+        self.measure(
+            "organizer: airdrop a badge",
+            &with_mint_budget(ix::airdrop_badge(main, &recipients[0].pubkey(), FREE_TIER)),
+            Role::Organizer,
+        )?;
+        self.measure(
+            "organizer: airdrop two badges",
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(2 * MINT_COMPUTE_UNITS),
+                ix::airdrop_badges(
+                    main,
+                    FREE_TIER,
+                    &[recipients[1].pubkey(), recipients[2].pubkey()],
+                ),
+            ],
+            Role::Organizer,
+        )?;
+
+        self.measure(
+            "organizer: hand over collection authority",
+            &[ix::transfer_collection_authority(main)],
+            Role::Organizer,
+        )?;
+
+        let late = self.wallet()?;
+        let flow = "attentee: mint with a delegated collection authority";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                main,
+                &late.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&late],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::check_in(main, &late.pubkey(), ATTENTANCE_CODE)],
+            &[&late],
+        )?;
+        self.harness.measure(
+            flow,
+            &with_mint_budget(ix::mint_nft(main, &late.pubkey(), FREE_TIER, None)),
+            &[&late],
+        )?;
+
+        let regular = self.wallet()?;
+        let occurrence = &fixture.occurrence;
+        let flow = "attentee: attend a series occurrence";
+        self.harness.measure(
+            flow,
+            &[ix::register_event(
+                occurrence,
+                &regular.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[&regular],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::check_in(occurrence, &regular.pubkey(), ATTENTANCE_CODE)],
+            &[&regular],
+        )?;
+        self.harness.measure(
+            flow,
+            &with_mint_budget(ix::mint_nft(occurrence, &regular.pubkey(), FREE_TIER, None)),
+            &[&regular],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::record_series_attendance(
+                &fixture.series,
+                occurrence,
+                &regular.pubkey(),
+            )],
+            &[&regular],
+        )?;
+
+        self.measure(
+            "organizer: hand over series collection authority",
+            &[ix::transfer_series_collection_authority(&fixture.series)],
+            Role::Organizer,
+        )?;
+
+        let flow = "organizer: edit and cancel event";
+        self.measure(
+            flow,
+            &[ix::edit_event(&fixture.small, EVENT_START, EVENT_END, 2)],
+            Role::Organizer,
+        )?;
+        self.measure(flow, &[ix::cancel_event(&fixture.small)], Role::Organizer)?;
+        self.harness.execute(
+            &[ix::cancel_registration(
+                &fixture.small,
+                &first.pubkey(),
+                FREE_TIER,
+            )],
+            &[&first],
+        )?;
+
+        let flow = "admin and organizer: pause and resume";
+        for paused in [true, false] {
+            self.measure(
+                flow,
+                &[ix::set_program_paused(&self.admin.pubkey(), paused)],
+                Role::Admin,
+            )?;
+        }
+        for paused in [true, false] {
+            self.measure(flow, &[ix::set_event_paused(main, paused)], Role::Organizer)?;
+        }
+
+        fixture.main_attentees.extend(recipients);
+        fixture.main_attentees.push(late);
+        Ok(())
+    }
+
+    fn after_event(&mut self, fixture: &Fixture) -> Result<(), BenchError> {
+        let attentee = &fixture.attentee;
+        let flow = "attentee: reclaim deposit and sponsor reward";
+        self.harness.measure(
+            flow,
+            &[ix::reclaim_deposit(&fixture.main, &attentee.pubkey())],
+            &[attentee],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::claim_sponsor_reward(
+                &fixture.main,
+                &fixture.pool,
+                &attentee.pubkey(),
+            )],
+            &[attentee],
+        )?;
+
+        self.harness.measure(
+            "client: read event stats",
+            &[ix::event_stats(&fixture.main)],
+            &[attentee],
+        )?;
+        Ok(())
+    }
+
+    fn after_claim_deadline(&mut self, fixture: &Fixture) -> Result<(), BenchError> {
+        self.measure(
+            "organizer: claim forfeited deposits",
+            &[ix::claim_forfeited_deposits(&fixture.main)],
+            Role::Organizer,
+        )?;
+
+        self.harness.measure(
+            "sponsor: reclaim pool",
+            &[ix::reclaim_sponsor_pool(&fixture.main, &fixture.pool)],
+            &[&fixture.sponsor],
+        )?;
+        Ok(())
+    }
+
+    fn after_grace_period(&mut self, fixture: &Fixture) -> Result<(), BenchError> {
+        let cranker = self.wallet()?;
+        let geofenced = &fixture.geofenced;
+        let flow = "crank: close stale registration and expired event";
+        self.harness.measure(
+            flow,
+            &[ix::close_stale_registration(
+                geofenced,
+                &fixture.geofenced_attentee.pubkey(),
+                &cranker.pubkey(),
+            )],
+            &[&cranker],
+        )?;
+        self.harness.measure(
+            flow,
+            &[ix::close_expired_event(geofenced, &cranker.pubkey())],
+            &[&cranker],
+        )?;
+
+        // open registrations keep an event from closing
+        for attentee in [&fixture.attentee]
+            .into_iter()
+            .chain(&fixture.main_attentees)
+        {
+            self.harness.execute(
+                &[ix::close_stale_registration(
+                    &fixture.main,
+                    &attentee.pubkey(),
+                    &cranker.pubkey(),
+                )],
+                &[&cranker],
+            )?;
+        }
+
+        let flow = "organizer: close events";
+        for event in [&fixture.main, &fixture.small] {
+            self.measure(flow, &[ix::close_event(event)], Role::Organizer)?;
+        }
+        Ok(())
+    }
+
+    fn measure(
+        &mut self,
+        flow: &str,
+        instructions: &[Instruction],
+        role: Role,
+    ) -> Result<(), BenchError> {
+        let signer = match role {
+            Role::Admin => &self.admin,
+            Role::Organizer => &self.organizer,
+        };
+        self.harness.measure(flow, instructions, &[signer])?;
+        Ok(())
+    }
+
+    /// A new wallet with enough lamports for rent, deposits and a ticket.
+    fn wallet(&mut self) -> Result<Keypair, BenchError> {
+        let wallet = Keypair::new();
+        self.harness
+            .airdrop(&wallet.pubkey(), 10 * LAMPORTS_PER_SOL)?;
+        Ok(wallet)
+    }
+
+    /// Creates an event with a free tier, outside of the report.
+    fn create_event(
+        &mut self,
+        name: &str,
+        start_time: i64,
+        end_time: i64,
+        total_attentees: u64,
+    ) -> Result<EventKeys, BenchError> {
+        let event = EventKeys::new(self.organizer.pubkey(), name);
+
+        self.harness.execute(
+            &with_mint_budget(ix::initialize_event(
+                &event,
+                start_time,
+                end_time,
+                total_attentees,
+            )),
+            &[&self.organizer],
+        )?;
+        self.harness.execute(
+            &[ix::create_ticket_tier(
+                &event,
+                FREE_TIER,
+                0,
+                EVENT_START,
+                EVENT_END,
+            )],
+            &[&self.organizer],
+        )?;
+        Ok(event)
+    }
+
+    /// Registers `attentee` for the free tier, outside of the report.
+    fn register(&mut self, event: &EventKeys, attentee: &Keypair) -> Result<(), BenchError> {
+        self.harness.execute(
+            &[ix::register_event(
+                event,
+                &attentee.pubkey(),
+                FREE_TIER,
+                vec![],
+                self.treasury,
+            )],
+            &[attentee],
+        )?;
+        Ok(())
+    }
+
+    /// Creates a token mint for `owner` and mints `SPONSOR_TOKENS` to their
+    /// associated token account, outside of the report.
+    fn create_token_mint(&mut self, owner: &Keypair) -> Result<Pubkey, BenchError> {
+        let mint = Keypair::new();
+        let owner_key = owner.pubkey();
+        let setup = |err: anchor_lang::prelude::ProgramError| BenchError::Setup(err.to_string());
+
+        self.harness.execute(
+            &[
+                system_instruction::create_account(
+                    &owner_key,
+                    &mint.pubkey(),
+                    self.harness.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &owner_key,
+                    None,
+                    6,
+                )
+                .map_err(setup)?,
+                associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+                    &owner_key,
+                    &owner_key,
+                    &mint.pubkey(),
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::mint_to(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &associated_token::get_associated_token_address(&owner_key, &mint.pubkey()),
+                    &owner_key,
+                    &[],
+                    SPONSOR_TOKENS,
+                )
+                .map_err(setup)?,
+            ],
+            &[owner, &mint],
+        )?;
+        Ok(mint.pubkey())
+    }
+}
+
+/// Who signs and pays for an admin or organizer transaction.
+enum Role {
+    Admin,
+    Organizer,
+}
+
+fn with_mint_budget(instruction: Instruction) -> [Instruction; 2] {
+    [
+        ComputeBudgetInstruction::set_compute_unit_limit(MINT_COMPUTE_UNITS),
+        instruction,
+    ]
+}
+
+fn hash_chain(mut value: [u8; 32], steps: u32) -> [u8; 32] {
+    for _ in 0..steps {
+        value = hash(&value).to_bytes();
+    }
+    value
+}
+
+fn codes() -> Vec<[u8; 32]> {
+    (0..CLAIM_CODES)
+        .map(|index| hash(&index.to_le_bytes()).to_bytes())
+        .collect()
+}
+
+/// Root of the claim code tree and the proof of code `index`. Pairs are
+/// hashed in sorted order, like `mint_nft` does when verifying.
+fn claim_code_proof(codes: &[[u8; 32]], index: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+    let mut level: Vec<[u8; 32]> = codes
+        .iter()
+        .enumerate()
+        .map(|(i, code)| ClaimCodes::leaf(i as u32, code))
+        .collect();
+    let mut position = index;
+    let mut proof = Vec::new();
+
+    while level.len() > 1 {
+        proof.push(level[position ^ 1]);
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let (a, b) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                hashv(&[&a, &b]).to_bytes()
+            })
+            .collect();
+        position /= 2;
+    }
+
+    (level[0], proof)
+}
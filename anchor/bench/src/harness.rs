@@ -0,0 +1,211 @@
+use std::path::Path;
+
+use anchor_spl::metadata::mpl_token_metadata;
+use litesvm::{types::TransactionMetadata, LiteSVM};
+use solana_sdk::{
+    account::Account,
+    bpf_loader, bpf_loader_upgradeable,
+    bpf_loader_upgradeable::UpgradeableLoaderState,
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+use crate::{
+    error::BenchError,
+    report::{Report, Step},
+};
+
+/// A LiteSVM bank with the event program and the token metadata program
+/// deployed, recording what each measured transaction costs.
+pub struct Harness {
+    svm: LiteSVM,
+    report: Report,
+}
+
+impl Harness {
+    /// Deploys the event program as an upgradeable program with
+    /// `upgrade_authority` as authority, so `initialize_config` accepts it as
+    /// admin.
+    pub fn new(
+        program: &Path,
+        metadata_program: &Path,
+        upgrade_authority: &Pubkey,
+    ) -> Result<Self, BenchError> {
+        let mut svm = LiteSVM::new();
+        let rent = |len: usize| svm.minimum_balance_for_rent_exemption(len);
+
+        let metadata_elf = std::fs::read(metadata_program)?;
+        let metadata_account = Account {
+            lamports: rent(metadata_elf.len()),
+            data: metadata_elf,
+            owner: bpf_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        };
+
+        // the program account only points at its program data account, which
+        // holds the upgrade authority followed by the ELF
+        let program_data_address =
+            Pubkey::find_program_address(&[counter::ID.as_ref()], &bpf_loader_upgradeable::ID).0;
+
+        let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        })
+        .map_err(|err| BenchError::Setup(err.to_string()))?;
+        program_data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+        program_data.extend(std::fs::read(program)?);
+
+        let program_account = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address: program_data_address,
+        })
+        .map_err(|err| BenchError::Setup(err.to_string()))?;
+
+        let program_data_account = Account {
+            lamports: rent(program_data.len()),
+            data: program_data,
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let program_account = Account {
+            lamports: rent(program_account.len()),
+            data: program_account,
+            owner: bpf_loader_upgradeable::ID,
+            executable: true,
+            rent_epoch: 0,
+        };
+
+        // the program data account has to exist before the program is loaded
+        for (address, account) in [
+            (mpl_token_metadata::ID, metadata_account),
+            (program_data_address, program_data_account),
+            (counter::ID, program_account),
+        ] {
+            svm.set_account(address, account)
+                .map_err(|err| BenchError::Setup(format!("loading {address}: {err:?}")))?;
+        }
+
+        Ok(Self {
+            svm,
+            report: Report::default(),
+        })
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn airdrop(&mut self, to: &Pubkey, lamports: u64) -> Result<(), BenchError> {
+        self.svm
+            .airdrop(to, lamports)
+            .map(|_| ())
+            .map_err(|failed| BenchError::Setup(format!("airdrop to {to}: {:?}", failed.err)))
+    }
+
+    pub fn minimum_balance(&self, len: usize) -> u64 {
+        self.svm.minimum_balance_for_rent_exemption(len)
+    }
+
+    /// Sends setup transactions that are not part of the report.
+    pub fn execute(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionMetadata, BenchError> {
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(transaction);
+
+        // the same instructions signed twice would otherwise be rejected as
+        // already processed
+        self.svm.expire_blockhash();
+
+        result.map_err(|failed| BenchError::Transaction {
+            error: format!("{:?}", failed.err),
+            logs: failed.meta.logs,
+        })
+    }
+
+    /// Sends a transaction paid by the first signer and records it as a step
+    /// of `flow`. Accounts created and rent only count accounts owned by a
+    /// program, lamports moved between wallets are not a cost of the
+    /// instruction.
+    pub fn measure(
+        &mut self,
+        flow: &str,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionMetadata, BenchError> {
+        let mut keys: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|instruction| instruction.accounts.iter().map(|meta| meta.pubkey))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let before: Vec<Option<usize>> = keys.iter().map(|key| self.data_len(key)).collect();
+        let meta = self.execute(instructions, signers)?;
+
+        let mut step = Step {
+            instruction: instruction_name(&meta.logs),
+            compute_units: meta.compute_units_consumed,
+            accounts_created: 0,
+            rent_lamports: 0,
+        };
+
+        for (key, before) in keys.iter().zip(before) {
+            let after = self.data_len(key);
+
+            if before.is_none() && after.is_some() {
+                step.accounts_created += 1;
+            }
+            step.rent_lamports += self.rent(after) - self.rent(before);
+        }
+
+        self.report.record(flow, step);
+        Ok(meta)
+    }
+
+    pub fn finish(self) -> Report {
+        self.report
+    }
+
+    /// Data length of a live account owned by a program.
+    fn data_len(&self, key: &Pubkey) -> Option<usize> {
+        self.svm
+            .get_account(key)
+            .filter(|account| account.lamports > 0 && account.owner != system_program::ID)
+            .map(|account| account.data.len())
+    }
+
+    fn rent(&self, data_len: Option<usize>) -> i64 {
+        data_len.map_or(0, |len| self.minimum_balance(len) as i64)
+    }
+}
+
+/// Name of the event program instruction, from the log Anchor writes when
+/// entering it.
+fn instruction_name(logs: &[String]) -> String {
+    let invoke = format!("Program {} invoke [1]", counter::ID);
+
+    logs.iter()
+        .skip_while(|log| **log != invoke)
+        .find_map(|log| log.strip_prefix("Program log: Instruction: "))
+        .unwrap_or("unknown")
+        .to_string()
+}
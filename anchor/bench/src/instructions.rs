@@ -0,0 +1,1108 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    metadata::mpl_token_metadata,
+    token,
+};
+use counter::{accounts, instruction, ClaimCode, RegistrationAnswer, RegistrationQuestion};
+use solana_sdk::{
+    bpf_loader_upgradeable, ed25519_program,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+};
+
+/// Static attendance code of every bench event.
+pub const ATTENTANCE_CODE: [u8; 32] = [7; 32];
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &counter::ID).0
+}
+
+fn metadata_pda(seeds: &[&[u8]]) -> Pubkey {
+    let program = mpl_token_metadata::ID;
+    let mut all = vec![b"metadata".as_ref(), program.as_ref()];
+    all.extend_from_slice(seeds);
+    Pubkey::find_program_address(&all, &program).0
+}
+
+pub fn config() -> Pubkey {
+    pda(&[b"config"])
+}
+
+pub fn organizer(creator: &Pubkey) -> Pubkey {
+    pda(&[b"organizer", creator.as_ref()])
+}
+
+pub fn profile(attentee: &Pubkey) -> Pubkey {
+    pda(&[b"profile", attentee.as_ref()])
+}
+
+/// Accounts of a collection mint, shared by events and series.
+#[derive(Clone)]
+pub struct Collection {
+    pub mint: Pubkey,
+    pub metadata: Pubkey,
+    pub master_edition: Pubkey,
+    pub authority_record: Pubkey,
+}
+
+impl Collection {
+    fn new(mint: Pubkey) -> Self {
+        Self {
+            mint,
+            metadata: metadata_pda(&[mint.as_ref()]),
+            master_edition: metadata_pda(&[mint.as_ref(), b"edition"]),
+            authority_record: metadata_pda(&[
+                mint.as_ref(),
+                b"collection_authority",
+                mint.as_ref(),
+            ]),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EventKeys {
+    pub creator: Pubkey,
+    pub name: String,
+    pub account: Pubkey,
+    pub collection: Collection,
+}
+
+impl EventKeys {
+    /// A standalone event, with a collection of its own.
+    pub fn new(creator: Pubkey, name: &str) -> Self {
+        let mint = pda(&[b"collection_mint", name.as_bytes()]);
+        Self::with_collection(creator, name, Collection::new(mint))
+    }
+
+    /// An occurrence of `series`, sharing the series collection.
+    pub fn occurrence(series: &SeriesKeys, name: &str) -> Self {
+        Self::with_collection(series.creator, name, series.collection.clone())
+    }
+
+    fn with_collection(creator: Pubkey, name: &str, collection: Collection) -> Self {
+        Self {
+            creator,
+            name: name.to_string(),
+            account: pda(&[b"event", creator.as_ref(), name.as_bytes()]),
+            collection,
+        }
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        pda(&[b"vault", self.account.as_ref()])
+    }
+
+    pub fn tier(&self, tier_id: u8) -> Pubkey {
+        pda(&[b"tier", self.account.as_ref(), &[tier_id]])
+    }
+
+    pub fn session(&self, session_id: u8) -> Pubkey {
+        pda(&[b"session", self.account.as_ref(), &[session_id]])
+    }
+
+    pub fn registration(&self, attentee: &Pubkey) -> Pubkey {
+        pda(&[b"attentee", self.account.as_ref(), attentee.as_ref()])
+    }
+
+    pub fn waitlist(&self, attentee: &Pubkey) -> Pubkey {
+        pda(&[b"waitlist", self.account.as_ref(), attentee.as_ref()])
+    }
+
+    pub fn questions(&self) -> Pubkey {
+        pda(&[b"questions", self.account.as_ref()])
+    }
+
+    pub fn claim_codes(&self) -> Pubkey {
+        pda(&[b"claim_codes", self.account.as_ref()])
+    }
+
+    pub fn badge(&self, attentee: &Pubkey) -> Badge {
+        let registration = self.registration(attentee);
+        let nft_mint = pda(&[b"nft_mint", registration.as_ref()]);
+
+        Badge {
+            registration,
+            nft_mint,
+            metadata: metadata_pda(&[nft_mint.as_ref()]),
+            master_edition: metadata_pda(&[nft_mint.as_ref(), b"edition"]),
+            destination: get_associated_token_address(attentee, &nft_mint),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SeriesKeys {
+    pub creator: Pubkey,
+    pub name: String,
+    pub account: Pubkey,
+    pub collection: Collection,
+}
+
+impl SeriesKeys {
+    pub fn new(creator: Pubkey, name: &str) -> Self {
+        Self {
+            creator,
+            name: name.to_string(),
+            account: pda(&[b"series", creator.as_ref(), name.as_bytes()]),
+            collection: Collection::new(pda(&[
+                b"series_collection_mint",
+                creator.as_ref(),
+                name.as_bytes(),
+            ])),
+        }
+    }
+}
+
+/// Accounts of the badge minted for a registration.
+pub struct Badge {
+    pub registration: Pubkey,
+    pub nft_mint: Pubkey,
+    pub metadata: Pubkey,
+    pub master_edition: Pubkey,
+    pub destination: Pubkey,
+}
+
+/// A pool of `mint` tokens a sponsor offers the attentees of an event.
+pub struct PoolKeys {
+    pub sponsor: Pubkey,
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub token_account: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(event: &EventKeys, sponsor: Pubkey, mint: Pubkey) -> Self {
+        let account = pda(&[
+            b"sponsor_pool",
+            event.account.as_ref(),
+            sponsor.as_ref(),
+            mint.as_ref(),
+        ]);
+
+        Self {
+            sponsor,
+            mint,
+            account,
+            token_account: get_associated_token_address(&account, &mint),
+        }
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: counter::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize_config(admin: &Pubkey, treasury: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin: *admin,
+            config: config(),
+            program: counter::ID,
+            program_data: Pubkey::find_program_address(
+                &[counter::ID.as_ref()],
+                &bpf_loader_upgradeable::ID,
+            )
+            .0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            fee_basis_points: 250,
+            treasury,
+            max_event_size: 1_000,
+        },
+    )
+}
+
+pub fn update_config(admin: &Pubkey, treasury: Pubkey) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            admin: *admin,
+            config: config(),
+        },
+        instruction::UpdateConfig {
+            admin: *admin,
+            fee_basis_points: 250,
+            treasury,
+            paused: false,
+            max_event_size: 1_000,
+        },
+    )
+}
+
+pub fn set_program_paused(admin: &Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            admin: *admin,
+            config: config(),
+        },
+        instruction::SetProgramPaused { paused },
+    )
+}
+
+pub fn initialize_event(
+    event: &EventKeys,
+    start_time: i64,
+    end_time: i64,
+    total_attentees: u64,
+) -> Instruction {
+    let collection = &event.collection;
+
+    build(
+        accounts::InitializeEvent {
+            payer: event.creator,
+            event_account: event.account,
+            organizer_account: organizer(&event.creator),
+            config: config(),
+            collection_mint: collection.mint,
+            collection_token_account: get_associated_token_address(
+                &event.creator,
+                &collection.mint,
+            ),
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeEvent {
+            name: event.name.clone(),
+            description: format!("{} cost report", event.name),
+            url: "https://example.com/event.json".to_string(),
+            attentance_code: ATTENTANCE_CODE,
+            start_time,
+            end_time,
+            total_attentees,
+            _collection_mint: collection.mint,
+            symbol: String::new(),
+            seller_fee_basis_points: 500,
+            creators: vec![],
+        },
+    )
+}
+
+pub fn initialize_series(series: &SeriesKeys) -> Instruction {
+    let collection = &series.collection;
+
+    build(
+        accounts::InitializeSeries {
+            payer: series.creator,
+            series_account: series.account,
+            collection_mint: collection.mint,
+            collection_token_account: get_associated_token_address(
+                &series.creator,
+                &collection.mint,
+            ),
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeSeries {
+            name: series.name.clone(),
+            description: format!("{} cost report", series.name),
+            url: "https://example.com/series.json".to_string(),
+            symbol: String::new(),
+            seller_fee_basis_points: 500,
+            creators: vec![],
+        },
+    )
+}
+
+pub fn create_series_occurrence(
+    series: &SeriesKeys,
+    event: &EventKeys,
+    start_time: i64,
+    end_time: i64,
+    total_attentees: u64,
+) -> Instruction {
+    build(
+        accounts::CreateSeriesOccurrence {
+            creator: series.creator,
+            series_account: series.account,
+            event_account: event.account,
+            organizer_account: organizer(&series.creator),
+            config: config(),
+            system_program: system_program::ID,
+        },
+        instruction::CreateSeriesOccurrence {
+            name: event.name.clone(),
+            description: format!("{} cost report", event.name),
+            attentance_code: ATTENTANCE_CODE,
+            start_time,
+            end_time,
+            total_attentees,
+        },
+    )
+}
+
+pub fn record_series_attendance(
+    series: &SeriesKeys,
+    event: &EventKeys,
+    attentee: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::RecordSeriesAttendance {
+            attentee: *attentee,
+            series_account: series.account,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+            attendance_account: pda(&[
+                b"series_attendance",
+                series.account.as_ref(),
+                attentee.as_ref(),
+            ]),
+            system_program: system_program::ID,
+        },
+        instruction::RecordSeriesAttendance {},
+    )
+}
+
+pub fn edit_event(
+    event: &EventKeys,
+    start_time: i64,
+    end_time: i64,
+    total_attentees: u64,
+) -> Instruction {
+    build(
+        accounts::EditEvent {
+            creator: event.creator,
+            event_account: event.account,
+            config: config(),
+            system_program: system_program::ID,
+        },
+        instruction::EditEvent {
+            _name: event.name.clone(),
+            attentance_code: ATTENTANCE_CODE,
+            start_time,
+            end_time,
+            total_attentees,
+        },
+    )
+}
+
+pub fn create_ticket_tier(
+    event: &EventKeys,
+    tier_id: u8,
+    price: u64,
+    registration_start: i64,
+    registration_end: i64,
+) -> Instruction {
+    build(
+        accounts::CreateTicketTier {
+            creator: event.creator,
+            event_account: event.account,
+            tier_account: event.tier(tier_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateTicketTier {
+            tier_id,
+            name: format!("tier {tier_id}"),
+            price,
+            capacity: 100,
+            registration_start,
+            registration_end,
+            uri: "https://example.com/badge.json".to_string(),
+        },
+    )
+}
+
+pub fn create_session(
+    event: &EventKeys,
+    session_id: u8,
+    start_time: i64,
+    end_time: i64,
+) -> Instruction {
+    build(
+        accounts::CreateSession {
+            creator: event.creator,
+            event_account: event.account,
+            session_account: event.session(session_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateSession {
+            session_id,
+            start_time,
+            end_time,
+            attentance_code: ATTENTANCE_CODE,
+        },
+    )
+}
+
+pub fn set_badge_requirement(event: &EventKeys, min_sessions_for_badge: u8) -> Instruction {
+    build(
+        accounts::SetBadgeRequirement {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetBadgeRequirement {
+            _name: event.name.clone(),
+            min_sessions_for_badge,
+        },
+    )
+}
+
+pub fn check_in_session(event: &EventKeys, attentee: &Pubkey, session_id: u8) -> Instruction {
+    build(
+        accounts::CheckInSession {
+            attentee: *attentee,
+            event_account: event.account,
+            session_account: event.session(session_id),
+            registration_account: event.registration(attentee),
+        },
+        instruction::CheckInSession {
+            session_id,
+            attentance_code: ATTENTANCE_CODE,
+        },
+    )
+}
+
+pub fn check_in(event: &EventKeys, attentee: &Pubkey, attentance_code: [u8; 32]) -> Instruction {
+    build(
+        accounts::CheckIn {
+            attentee: *attentee,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+        },
+        instruction::CheckIn { attentance_code },
+    )
+}
+
+pub fn set_event_deposit(
+    event: &EventKeys,
+    deposit_lamports: u64,
+    deposit_claim_deadline: i64,
+) -> Instruction {
+    build(
+        accounts::SetEventDeposit {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetEventDeposit {
+            _name: event.name.clone(),
+            deposit_lamports,
+            deposit_beneficiary: event.creator,
+            deposit_claim_deadline,
+        },
+    )
+}
+
+pub fn set_code_rotation(
+    event: &EventKeys,
+    commitment: [u8; 32],
+    code_rotation_period: u32,
+) -> Instruction {
+    build(
+        accounts::SetCodeRotation {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetCodeRotation {
+            _name: event.name.clone(),
+            commitment,
+            code_rotation_period,
+        },
+    )
+}
+
+pub fn set_geofence(
+    event: &EventKeys,
+    geofence: &str,
+    attestation_authority: Pubkey,
+) -> Instruction {
+    build(
+        accounts::SetGeofence {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetGeofence {
+            _name: event.name.clone(),
+            geofence: geofence.to_string(),
+            attestation_authority,
+        },
+    )
+}
+
+/// Ed25519 program instruction carrying a location attestation, with the
+/// key, signature and message all in its own data.
+pub fn location_attestation(
+    authority: &Keypair,
+    attentee: &Pubkey,
+    event: &EventKeys,
+    geohash: &[u8; counter::ATTESTED_GEOHASH_LENGTH],
+    timestamp: i64,
+) -> Instruction {
+    const PUBLIC_KEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBLIC_KEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+
+    let mut message = Vec::new();
+    message.extend_from_slice(attentee.as_ref());
+    message.extend_from_slice(event.account.as_ref());
+    message.extend_from_slice(geohash);
+    message.extend_from_slice(&timestamp.to_le_bytes());
+
+    let signature = authority.sign_message(&message);
+
+    let mut data = vec![1, 0];
+    for offset in [
+        SIGNATURE_OFFSET,
+        u16::MAX,
+        PUBLIC_KEY_OFFSET,
+        u16::MAX,
+        MESSAGE_OFFSET,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(authority.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+pub fn check_in_with_attestation(event: &EventKeys, attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::CheckInWithAttestation {
+            attentee: *attentee,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::CheckInWithAttestation {},
+    )
+}
+
+pub fn transfer_collection_authority(event: &EventKeys) -> Instruction {
+    let collection = &event.collection;
+
+    build(
+        accounts::TransferCollectionAuthority {
+            creator: event.creator,
+            event_account: event.account,
+            collection_mint: collection.mint,
+            metadata: collection.metadata,
+            collection_authority_record: collection.authority_record,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferCollectionAuthority {
+            _name: event.name.clone(),
+        },
+    )
+}
+
+pub fn transfer_series_collection_authority(series: &SeriesKeys) -> Instruction {
+    let collection = &series.collection;
+
+    build(
+        accounts::TransferSeriesCollectionAuthority {
+            creator: series.creator,
+            series_account: series.account,
+            collection_mint: collection.mint,
+            metadata: collection.metadata,
+            collection_authority_record: collection.authority_record,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferSeriesCollectionAuthority {
+            _name: series.name.clone(),
+        },
+    )
+}
+
+pub fn close_event(event: &EventKeys) -> Instruction {
+    build(
+        accounts::CloseEvent {
+            payer: event.creator,
+            event_account: event.account,
+            organizer_account: organizer(&event.creator),
+            vault: event.vault(),
+            system_program: system_program::ID,
+        },
+        instruction::CloseEvent {},
+    )
+}
+
+pub fn cancel_event(event: &EventKeys) -> Instruction {
+    build(
+        accounts::CancelEvent {
+            creator: event.creator,
+            event_account: event.account,
+            organizer_account: organizer(&event.creator),
+        },
+        instruction::CancelEvent {
+            _name: event.name.clone(),
+        },
+    )
+}
+
+pub fn join_waitlist(event: &EventKeys, attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::JoinWaitlist {
+            attentee: *attentee,
+            payer: *attentee,
+            event_account: event.account,
+            waitlist_entry: event.waitlist(attentee),
+            registration_account: event.registration(attentee),
+            system_program: system_program::ID,
+        },
+        instruction::JoinWaitlist {},
+    )
+}
+
+pub fn leave_waitlist(event: &EventKeys, attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::LeaveWaitlist {
+            attentee: *attentee,
+            event_account: event.account,
+            waitlist_entry: event.waitlist(attentee),
+            payer: *attentee,
+        },
+        instruction::LeaveWaitlist {},
+    )
+}
+
+pub fn event_stats(event: &EventKeys) -> Instruction {
+    build(
+        accounts::GetEventStats {
+            event_account: event.account,
+        },
+        instruction::EventStats {},
+    )
+}
+
+pub fn set_claim_codes(event: &EventKeys, merkle_root: [u8; 32], code_count: u32) -> Instruction {
+    build(
+        accounts::SetClaimCodes {
+            creator: event.creator,
+            event_account: event.account,
+            claim_codes_account: event.claim_codes(),
+            system_program: system_program::ID,
+        },
+        instruction::SetClaimCodes {
+            _name: event.name.clone(),
+            merkle_root,
+            code_count,
+        },
+    )
+}
+
+pub fn set_walk_in_tier(event: &EventKeys, tier_id: Option<u8>) -> Instruction {
+    build(
+        accounts::SetWalkInTier {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetWalkInTier {
+            _name: event.name.clone(),
+            tier_id,
+        },
+    )
+}
+
+pub fn set_event_paused(event: &EventKeys, paused: bool) -> Instruction {
+    build(
+        accounts::SetEventPaused {
+            creator: event.creator,
+            event_account: event.account,
+        },
+        instruction::SetEventPaused {
+            _name: event.name.clone(),
+            paused,
+        },
+    )
+}
+
+pub fn set_registration_questions(
+    event: &EventKeys,
+    questions: Vec<RegistrationQuestion>,
+) -> Instruction {
+    build(
+        accounts::SetRegistrationQuestions {
+            creator: event.creator,
+            event_account: event.account,
+            questions_account: event.questions(),
+            system_program: system_program::ID,
+        },
+        instruction::SetRegistrationQuestions {
+            _name: event.name.clone(),
+            questions,
+        },
+    )
+}
+
+pub fn update_profile(attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::UpdateProfile {
+            attentee: *attentee,
+            profile_account: profile(attentee),
+            system_program: system_program::ID,
+        },
+        instruction::UpdateProfile {
+            display_name: "bench attentee".to_string(),
+            contact_hash: [1; 32],
+            social_handle: "@bench".to_string(),
+            preferences: 1,
+        },
+    )
+}
+
+pub fn close_profile(attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseProfile {
+            attentee: *attentee,
+            profile_account: profile(attentee),
+        },
+        instruction::CloseProfile {},
+    )
+}
+
+/// Registers `attentee`, paying for their own registration. The questions
+/// account is only passed along with answers.
+pub fn register_event(
+    event: &EventKeys,
+    attentee: &Pubkey,
+    tier_id: u8,
+    answers: Vec<RegistrationAnswer>,
+    treasury: Pubkey,
+) -> Instruction {
+    build(
+        accounts::RegisterEvent {
+            attentee: *attentee,
+            payer: *attentee,
+            event_account: event.account,
+            creator: event.creator,
+            vault: event.vault(),
+            tier_account: event.tier(tier_id),
+            registration_account: event.registration(attentee),
+            questions_account: (!answers.is_empty()).then(|| event.questions()),
+            waitlist_entry: event.waitlist(attentee),
+            waitlist_payer: None,
+            config: config(),
+            treasury,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterEvent { tier_id, answers },
+    )
+}
+
+pub fn cancel_registration(event: &EventKeys, attentee: &Pubkey, tier_id: u8) -> Instruction {
+    build(
+        accounts::CancelRegistration {
+            attentee: *attentee,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+            payer: *attentee,
+            tier_account: event.tier(tier_id),
+            vault: event.vault(),
+            system_program: system_program::ID,
+        },
+        instruction::CancelRegistration {},
+    )
+}
+
+pub fn reclaim_deposit(event: &EventKeys, attentee: &Pubkey) -> Instruction {
+    build(
+        accounts::ReclaimDeposit {
+            attentee: *attentee,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+            vault: event.vault(),
+            system_program: system_program::ID,
+        },
+        instruction::ReclaimDeposit {},
+    )
+}
+
+pub fn claim_forfeited_deposits(event: &EventKeys) -> Instruction {
+    build(
+        accounts::ClaimForfeitedDeposits {
+            creator: event.creator,
+            event_account: event.account,
+            beneficiary: event.creator,
+            vault: event.vault(),
+            config: config(),
+            system_program: system_program::ID,
+        },
+        instruction::ClaimForfeitedDeposits {},
+    )
+}
+
+pub fn create_sponsor_pool(
+    event: &EventKeys,
+    pool: &PoolKeys,
+    amount_per_attentee: u64,
+    refund_deadline: i64,
+) -> Instruction {
+    build(
+        accounts::CreateSponsorPool {
+            sponsor: pool.sponsor,
+            event_account: event.account,
+            mint: pool.mint,
+            sponsor_pool: pool.account,
+            pool_token_account: pool.token_account,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSponsorPool {
+            amount_per_attentee,
+            refund_deadline,
+        },
+    )
+}
+
+pub fn fund_sponsor_pool(
+    event: &EventKeys,
+    pool: &PoolKeys,
+    funder_token_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::FundSponsorPool {
+            funder: pool.sponsor,
+            event_account: event.account,
+            sponsor_pool: pool.account,
+            mint: pool.mint,
+            config: config(),
+            funder_token_account,
+            pool_token_account: pool.token_account,
+            token_program: token::ID,
+        },
+        instruction::FundSponsorPool { amount },
+    )
+}
+
+pub fn claim_sponsor_reward(event: &EventKeys, pool: &PoolKeys, attentee: &Pubkey) -> Instruction {
+    let registration = event.registration(attentee);
+
+    build(
+        accounts::ClaimSponsorReward {
+            attentee: *attentee,
+            payer: *attentee,
+            event_account: event.account,
+            registration_account: registration,
+            sponsor_pool: pool.account,
+            sponsor_claim: pda(&[
+                b"sponsor_claim",
+                pool.account.as_ref(),
+                registration.as_ref(),
+            ]),
+            mint: pool.mint,
+            pool_token_account: pool.token_account,
+            attentee_token_account: get_associated_token_address(attentee, &pool.mint),
+            config: config(),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimSponsorReward {},
+    )
+}
+
+pub fn reclaim_sponsor_pool(event: &EventKeys, pool: &PoolKeys) -> Instruction {
+    build(
+        accounts::ReclaimSponsorPool {
+            sponsor: pool.sponsor,
+            event_account: event.account,
+            sponsor_pool: pool.account,
+            mint: pool.mint,
+            pool_token_account: pool.token_account,
+            sponsor_token_account: get_associated_token_address(&pool.sponsor, &pool.mint),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ReclaimSponsorPool {},
+    )
+}
+
+pub fn close_stale_registration(
+    event: &EventKeys,
+    attentee: &Pubkey,
+    cranker: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CloseStaleRegistration {
+            cranker: *cranker,
+            event_account: event.account,
+            registration_account: event.registration(attentee),
+            payer: *attentee,
+            attentee: *attentee,
+            vault: event.vault(),
+            system_program: system_program::ID,
+        },
+        instruction::CloseStaleRegistration {},
+    )
+}
+
+pub fn close_expired_event(event: &EventKeys, cranker: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseExpiredEvent {
+            cranker: *cranker,
+            event_account: event.account,
+            creator: event.creator,
+            organizer_account: organizer(&event.creator),
+            vault: event.vault(),
+            config: config(),
+        },
+        instruction::CloseExpiredEvent {},
+    )
+}
+
+pub fn mint_nft(
+    event: &EventKeys,
+    attentee: &Pubkey,
+    tier_id: u8,
+    claim_code: Option<ClaimCode>,
+) -> Instruction {
+    let badge = event.badge(attentee);
+    let collection = &event.collection;
+
+    build(
+        accounts::MintNft {
+            attentee: *attentee,
+            payer: *attentee,
+            event_account: event.account,
+            registration_account: badge.registration,
+            tier_account: event.tier(tier_id),
+            collection_mint: collection.mint,
+            nft_mint: badge.nft_mint,
+            child_nft_metadata: badge.metadata,
+            child_nft_master_edition: badge.master_edition,
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            collection_authority_record: collection.authority_record,
+            destination: badge.destination,
+            config: config(),
+            claim_codes_account: claim_code.as_ref().map(|_| event.claim_codes()),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::MintNft { claim_code },
+    )
+}
+
+pub fn walk_in_mint(event: &EventKeys, attentee: &Pubkey, tier_id: u8) -> Instruction {
+    let badge = event.badge(attentee);
+    let collection = &event.collection;
+
+    build(
+        accounts::WalkInMint {
+            attentee: *attentee,
+            payer: *attentee,
+            event_account: event.account,
+            registration_account: badge.registration,
+            tier_account: event.tier(tier_id),
+            collection_mint: collection.mint,
+            nft_mint: badge.nft_mint,
+            child_nft_metadata: badge.metadata,
+            child_nft_master_edition: badge.master_edition,
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            collection_authority_record: collection.authority_record,
+            destination: badge.destination,
+            config: config(),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::WalkInMint {
+            tier_id,
+            attentance_code: ATTENTANCE_CODE,
+        },
+    )
+}
+
+pub fn airdrop_badge(event: &EventKeys, attentee: &Pubkey, tier_id: u8) -> Instruction {
+    let badge = event.badge(attentee);
+    let collection = &event.collection;
+
+    build(
+        accounts::AirdropBadge {
+            creator: event.creator,
+            attentee: *attentee,
+            event_account: event.account,
+            registration_account: badge.registration,
+            tier_account: event.tier(tier_id),
+            collection_mint: collection.mint,
+            nft_mint: badge.nft_mint,
+            child_nft_metadata: badge.metadata,
+            child_nft_master_edition: badge.master_edition,
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            collection_authority_record: collection.authority_record,
+            destination: badge.destination,
+            config: config(),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::AirdropBadge {},
+    )
+}
+
+/// Airdrops badges to `attentees`, all registered for `tier_id`.
+pub fn airdrop_badges(event: &EventKeys, tier_id: u8, attentees: &[Pubkey]) -> Instruction {
+    let collection = &event.collection;
+
+    let mut airdrop = build(
+        accounts::AirdropBadges {
+            creator: event.creator,
+            event_account: event.account,
+            tier_account: event.tier(tier_id),
+            collection_mint: collection.mint,
+            metadata: collection.metadata,
+            master_edition: collection.master_edition,
+            collection_authority_record: collection.authority_record,
+            config: config(),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::AirdropBadges {},
+    );
+
+    for attentee in attentees {
+        let badge = event.badge(attentee);
+
+        airdrop.accounts.extend([
+            AccountMeta::new_readonly(*attentee, false),
+            AccountMeta::new(badge.registration, false),
+            AccountMeta::new(badge.nft_mint, false),
+            AccountMeta::new(badge.metadata, false),
+            AccountMeta::new(badge.master_edition, false),
+            AccountMeta::new(badge.destination, false),
+        ]);
+    }
+
+    airdrop
+}
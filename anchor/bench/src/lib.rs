@@ -0,0 +1,15 @@
+//! Compute unit and rent cost report for the event program.
+//!
+//! Every instruction runs in LiteSVM against the program built by
+//! `anchor build`, grouped into flows such as registering, checking in and
+//! minting a badge. Each step records the compute units it consumed, the
+//! program owned accounts it created and the rent it locked, and the flow
+//! totals are compared with a baseline report so cost regressions fail the
+//! run.
+
+pub mod config;
+pub mod error;
+pub mod flows;
+pub mod harness;
+pub mod instructions;
+pub mod report;
@@ -0,0 +1,25 @@
+use bench::{config::Config, error::BenchError, flows, report::Report};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_env()?;
+    let report = flows::run(&config.program, &config.metadata_program)?;
+    print!("{report}");
+
+    if config.update_baseline {
+        report.save(&config.baseline)?;
+        println!("baseline written to {}", config.baseline.display());
+        return Ok(());
+    }
+
+    let baseline = Report::load(&config.baseline)?;
+    let regressions = report.regressions(&baseline, config.threshold_percent);
+
+    for regression in &regressions {
+        eprintln!("{regression}");
+    }
+
+    if !regressions.is_empty() {
+        return Err(BenchError::Regression(regressions.len()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::{fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BenchError;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub flows: Vec<Flow>,
+}
+
+/// A user journey, such as registering, checking in and minting a badge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Flow {
+    pub name: String,
+    pub steps: Vec<Step>,
+}
+
+/// One transaction of a flow. `rent_lamports` is the rent locked in program
+/// owned accounts, negative when the transaction closes more than it opens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub instruction: String,
+    pub compute_units: u64,
+    pub accounts_created: u64,
+    pub rent_lamports: i64,
+}
+
+/// A difference to the baseline that needs a new baseline to pass.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Regression {
+    /// The flow's totals grew past what the baseline allows.
+    Cost {
+        flow: String,
+        metric: &'static str,
+        baseline: i64,
+        current: i64,
+    },
+    /// The flow is new or was renamed, the baseline has no costs for it.
+    NewFlow(String),
+    /// The baseline flow no longer runs, it was removed or renamed.
+    MissingFlow(String),
+}
+
+impl Flow {
+    pub fn totals(&self) -> Step {
+        self.steps.iter().fold(
+            Step {
+                instruction: "total".to_string(),
+                compute_units: 0,
+                accounts_created: 0,
+                rent_lamports: 0,
+            },
+            |mut total, step| {
+                total.compute_units += step.compute_units;
+                total.accounts_created += step.accounts_created;
+                total.rent_lamports += step.rent_lamports;
+                total
+            },
+        )
+    }
+}
+
+impl Report {
+    pub fn record(&mut self, flow: &str, step: Step) {
+        match self.flows.iter_mut().find(|f| f.name == flow) {
+            Some(flow) => flow.steps.push(step),
+            None => self.flows.push(Flow {
+                name: flow.to_string(),
+                steps: vec![step],
+            }),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BenchError> {
+        if !path.exists() {
+            return Err(BenchError::MissingBaseline(path.display().to_string()));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BenchError> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Compares flow totals with `baseline`. Compute units and rent may grow
+    /// by `threshold_percent`, the number of accounts created may not grow at
+    /// all. Flows only one of the reports has are regressions too, so a
+    /// renamed flow cannot slip past the comparison.
+    pub fn regressions(&self, baseline: &Report, threshold_percent: u64) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+
+        for flow in &self.flows {
+            let Some(baseline_flow) = baseline.flows.iter().find(|f| f.name == flow.name) else {
+                regressions.push(Regression::NewFlow(flow.name.clone()));
+                continue;
+            };
+            let current = flow.totals();
+            let allowed = baseline_flow.totals();
+
+            let metrics = [
+                (
+                    "compute units",
+                    allowed.compute_units as i64,
+                    current.compute_units as i64,
+                    threshold_percent,
+                ),
+                (
+                    "accounts created",
+                    allowed.accounts_created as i64,
+                    current.accounts_created as i64,
+                    0,
+                ),
+                (
+                    "rent lamports",
+                    allowed.rent_lamports,
+                    current.rent_lamports,
+                    threshold_percent,
+                ),
+            ];
+
+            for (metric, baseline, current, threshold_percent) in metrics {
+                let limit = baseline + baseline.abs() * threshold_percent as i64 / 100;
+
+                if current > limit {
+                    regressions.push(Regression::Cost {
+                        flow: flow.name.clone(),
+                        metric,
+                        baseline,
+                        current,
+                    });
+                }
+            }
+        }
+
+        for flow in &baseline.flows {
+            if !self.flows.iter().any(|f| f.name == flow.name) {
+                regressions.push(Regression::MissingFlow(flow.name.clone()));
+            }
+        }
+
+        regressions
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<48} {:>14} {:>9} {:>14}",
+            "flow / instruction", "compute units", "accounts", "rent lamports"
+        )?;
+
+        for flow in &self.flows {
+            let total = flow.totals();
+            writeln!(
+                f,
+                "{:<48} {:>14} {:>9} {:>14}",
+                flow.name, total.compute_units, total.accounts_created, total.rent_lamports
+            )?;
+
+            for step in &flow.steps {
+                writeln!(
+                    f,
+                    "  {:<46} {:>14} {:>9} {:>14}",
+                    step.instruction, step.compute_units, step.accounts_created, step.rent_lamports
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regression::Cost {
+                flow,
+                metric,
+                baseline,
+                current,
+            } => write!(f, "{flow}: {metric} went from {baseline} to {current}"),
+            Regression::NewFlow(flow) => write!(f, "{flow}: not in the baseline"),
+            Regression::MissingFlow(flow) => write!(f, "{flow}: in the baseline but did not run"),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use bench::{
+    config::Config,
+    flows,
+    report::{Flow, Regression, Report, Step},
+};
+
+/// Every instruction of the program, by the name its log line gives it.
+const INSTRUCTIONS: [&str; 44] = [
+    "InitializeConfig",
+    "UpdateConfig",
+    "SetProgramPaused",
+    "InitializeEvent",
+    "InitializeSeries",
+    "CreateSeriesOccurrence",
+    "RecordSeriesAttendance",
+    "EditEvent",
+    "CreateTicketTier",
+    "CreateSession",
+    "SetBadgeRequirement",
+    "CheckInSession",
+    "CheckIn",
+    "SetEventDeposit",
+    "SetCodeRotation",
+    "SetGeofence",
+    "CheckInWithAttestation",
+    "TransferCollectionAuthority",
+    "TransferSeriesCollectionAuthority",
+    "CloseEvent",
+    "CancelEvent",
+    "JoinWaitlist",
+    "LeaveWaitlist",
+    "EventStats",
+    "SetClaimCodes",
+    "SetWalkInTier",
+    "SetEventPaused",
+    "SetRegistrationQuestions",
+    "UpdateProfile",
+    "CloseProfile",
+    "RegisterEvent",
+    "CancelRegistration",
+    "ReclaimDeposit",
+    "ClaimForfeitedDeposits",
+    "CreateSponsorPool",
+    "FundSponsorPool",
+    "ClaimSponsorReward",
+    "ReclaimSponsorPool",
+    "CloseStaleRegistration",
+    "CloseExpiredEvent",
+    "MintNft",
+    "WalkInMint",
+    "AirdropBadge",
+    "AirdropBadges",
+];
+
+fn step(instruction: &str, compute_units: u64, accounts_created: u64, rent_lamports: i64) -> Step {
+    Step {
+        instruction: instruction.to_string(),
+        compute_units,
+        accounts_created,
+        rent_lamports,
+    }
+}
+
+fn report(steps: Vec<Step>) -> Report {
+    Report {
+        flows: vec![Flow {
+            name: "attentee: register, check in and mint".to_string(),
+            steps,
+        }],
+    }
+}
+
+fn metrics(regressions: &[Regression]) -> Vec<&'static str> {
+    regressions
+        .iter()
+        .map(|regression| match regression {
+            Regression::Cost { metric, .. } => *metric,
+            Regression::NewFlow(_) => "new flow",
+            Regression::MissingFlow(_) => "missing flow",
+        })
+        .collect()
+}
+
+#[test]
+fn totals_sum_the_steps_of_a_flow() {
+    let mut report = Report::default();
+    report.record("flow", step("RegisterEvent", 20_000, 1, 2_000_000));
+    report.record("flow", step("CancelRegistration", 10_000, 0, -2_000_000));
+
+    let totals = report.flows[0].totals();
+    assert_eq!(report.flows.len(), 1);
+    assert_eq!(totals.compute_units, 30_000);
+    assert_eq!(totals.accounts_created, 1);
+    assert_eq!(totals.rent_lamports, 0);
+}
+
+#[test]
+fn allows_growth_within_the_threshold() {
+    let baseline = report(vec![step("MintNft", 100_000, 4, 10_000_000)]);
+    let current = report(vec![step("MintNft", 105_000, 4, 10_500_000)]);
+
+    assert!(current.regressions(&baseline, 5).is_empty());
+}
+
+#[test]
+fn flags_growth_past_the_threshold() {
+    let baseline = report(vec![step("MintNft", 100_000, 4, 10_000_000)]);
+    let current = report(vec![step("MintNft", 105_001, 5, 10_000_000)]);
+
+    let regressions = current.regressions(&baseline, 5);
+    assert_eq!(metrics(&regressions), ["compute units", "accounts created"]);
+    assert!(matches!(
+        regressions[0],
+        Regression::Cost {
+            current: 105_001,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn flags_smaller_refunds() {
+    let baseline = report(vec![step("CloseEvent", 5_000, 0, -10_000_000)]);
+    let current = report(vec![step("CloseEvent", 5_000, 0, -9_000_000)]);
+
+    let regressions = current.regressions(&baseline, 5);
+    assert_eq!(metrics(&regressions), ["rent lamports"]);
+}
+
+#[test]
+fn flags_flows_only_one_report_has() {
+    let baseline = report(vec![step("MintNft", 100_000, 4, 10_000_000)]);
+    let mut current = Report::default();
+    current.record(
+        "attentee: register and mint",
+        step("MintNft", 100_000, 4, 10_000_000),
+    );
+
+    assert_eq!(
+        current.regressions(&baseline, 5),
+        [
+            Regression::NewFlow("attentee: register and mint".to_string()),
+            Regression::MissingFlow("attentee: register, check in and mint".to_string()),
+        ]
+    );
+}
+
+#[test]
+#[ignore = "needs anchor build and the token metadata program dumped to bench/fixtures"]
+fn runs_every_instruction() {
+    let config = Config::from_env().unwrap();
+    let report = flows::run(&config.program, &config.metadata_program).unwrap();
+
+    let instructions: HashSet<&str> = report
+        .flows
+        .iter()
+        .flat_map(|flow| &flow.steps)
+        .map(|step| step.instruction.as_str())
+        .collect();
+
+    assert_eq!(instructions, HashSet::from(INSTRUCTIONS));
+}
@@ -0,0 +1,122 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    db::{CheckInRow, EventRow, MintRow, OrganizerRow, ProfileRow, RegistrationRow, Store},
+    error::IndexError,
+};
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    pub creator: Option<String>,
+}
+
+pub fn router(store: Arc<Store>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/events", get(events))
+        .route("/events/:event", get(event))
+        .route("/events/:event/registrations", get(event_registrations))
+        .route("/events/:event/check-ins", get(check_ins))
+        .route("/events/:event/mints", get(mints))
+        .route(
+            "/attentees/:attentee/registrations",
+            get(attentee_registrations),
+        )
+        .route("/attentees/:attentee/profile", get(profile))
+        .route("/organizers", get(organizers))
+        .route("/organizers/:organizer", get(organizer))
+        .with_state(store)
+}
+
+fn parse_key(key: &str) -> Result<Pubkey, IndexError> {
+    Pubkey::from_str(key).map_err(|_| IndexError::InvalidKey(key.to_string()))
+}
+
+async fn health(State(store): State<Arc<Store>>) -> Result<Json<serde_json::Value>, IndexError> {
+    Ok(Json(serde_json::json!({ "cursor": store.cursor().await? })))
+}
+
+async fn events(
+    State(store): State<Arc<Store>>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Json<Vec<EventRow>>, IndexError> {
+    let creator = query.creator.as_deref().map(parse_key).transpose()?;
+    Ok(Json(store.events(creator.as_ref()).await?))
+}
+
+async fn event(
+    State(store): State<Arc<Store>>,
+    Path(event): Path<String>,
+) -> Result<Json<EventRow>, IndexError> {
+    store
+        .event(&parse_key(&event)?)
+        .await?
+        .map(Json)
+        .ok_or(IndexError::NotFound)
+}
+
+async fn event_registrations(
+    State(store): State<Arc<Store>>,
+    Path(event): Path<String>,
+) -> Result<Json<Vec<RegistrationRow>>, IndexError> {
+    Ok(Json(store.event_registrations(&parse_key(&event)?).await?))
+}
+
+async fn check_ins(
+    State(store): State<Arc<Store>>,
+    Path(event): Path<String>,
+) -> Result<Json<Vec<CheckInRow>>, IndexError> {
+    Ok(Json(store.check_ins(&parse_key(&event)?).await?))
+}
+
+async fn mints(
+    State(store): State<Arc<Store>>,
+    Path(event): Path<String>,
+) -> Result<Json<Vec<MintRow>>, IndexError> {
+    Ok(Json(store.mints(&parse_key(&event)?).await?))
+}
+
+async fn attentee_registrations(
+    State(store): State<Arc<Store>>,
+    Path(attentee): Path<String>,
+) -> Result<Json<Vec<RegistrationRow>>, IndexError> {
+    Ok(Json(
+        store.attentee_registrations(&parse_key(&attentee)?).await?,
+    ))
+}
+
+async fn profile(
+    State(store): State<Arc<Store>>,
+    Path(attentee): Path<String>,
+) -> Result<Json<ProfileRow>, IndexError> {
+    store
+        .profile(&parse_key(&attentee)?)
+        .await?
+        .map(Json)
+        .ok_or(IndexError::NotFound)
+}
+
+async fn organizers(
+    State(store): State<Arc<Store>>,
+) -> Result<Json<Vec<OrganizerRow>>, IndexError> {
+    Ok(Json(store.organizers().await?))
+}
+
+async fn organizer(
+    State(store): State<Arc<Store>>,
+    Path(organizer): Path<String>,
+) -> Result<Json<OrganizerRow>, IndexError> {
+    store
+        .organizer(&parse_key(&organizer)?)
+        .await?
+        .map(Json)
+        .ok_or(IndexError::NotFound)
+}
@@ -0,0 +1,42 @@
+use std::{env, str::FromStr};
+
+use crate::error::IndexError;
+
+/// Indexer settings, read from the environment:
+///
+/// - `INDEXER_DATABASE_URL`: `sqlite://...` or `postgres://...`, defaults to `sqlite://indexer.db?mode=rwc`
+/// - `INDEXER_RPC_URL`: defaults to the local validator
+/// - `INDEXER_WS_URL`: defaults to the local validator's pubsub port
+/// - `INDEXER_BIND_ADDR`: query API address, defaults to `127.0.0.1:8081`
+/// - `INDEXER_REPLAY`: replay program transactions missed since the last run, defaults to `true`
+pub struct Config {
+    pub database_url: String,
+    pub rpc_url: String,
+    pub ws_url: String,
+    pub bind_addr: String,
+    pub replay: bool,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, IndexError> {
+        Ok(Self {
+            database_url: optional(
+                "INDEXER_DATABASE_URL",
+                "sqlite://indexer.db?mode=rwc".to_string(),
+            )?,
+            rpc_url: optional("INDEXER_RPC_URL", "http://127.0.0.1:8899".to_string())?,
+            ws_url: optional("INDEXER_WS_URL", "ws://127.0.0.1:8900".to_string())?,
+            bind_addr: optional("INDEXER_BIND_ADDR", "127.0.0.1:8081".to_string())?,
+            replay: optional("INDEXER_REPLAY", true)?,
+        })
+    }
+}
+
+fn optional<T: FromStr>(name: &str, default: T) -> Result<T, IndexError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| IndexError::Config(format!("invalid value for {name}"))),
+        Err(_) => Ok(default),
+    }
+}
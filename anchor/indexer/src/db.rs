@@ -0,0 +1,631 @@
+use counter::{AttendeeProfile, Event, EventRegistration, OrganizerProfile, RegistrationAnswer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{any::AnyPoolOptions, AnyPool, FromRow};
+
+use crate::{decode::ProgramEvent, error::IndexError};
+
+/// Tables shared by SQLite and Postgres. Unsigned program values are stored
+/// as `BIGINT`, `sessions_attended` keeps its bit pattern. Event level
+/// check-ins use `session_id` -1, so it can stay part of the primary key.
+pub const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS events (
+        pubkey TEXT PRIMARY KEY,
+        creator TEXT NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        url TEXT NOT NULL,
+        start_time BIGINT NOT NULL,
+        end_time BIGINT NOT NULL,
+        total_attentees BIGINT NOT NULL,
+        registered_attentees BIGINT NOT NULL,
+        checked_in_attentees BIGINT NOT NULL,
+        minted_attentees BIGINT NOT NULL,
+        cancelled_registrations BIGINT NOT NULL,
+        waitlisted_attentees BIGINT NOT NULL,
+        cancelled BOOLEAN NOT NULL,
+        collection_mint TEXT NOT NULL,
+        series TEXT,
+        slot BIGINT NOT NULL,
+        closed BOOLEAN NOT NULL DEFAULT FALSE
+    )",
+    "CREATE INDEX IF NOT EXISTS events_creator ON events (creator)",
+    "CREATE TABLE IF NOT EXISTS registrations (
+        pubkey TEXT PRIMARY KEY,
+        event TEXT NOT NULL,
+        attentee TEXT NOT NULL,
+        payer TEXT,
+        tier BIGINT NOT NULL,
+        attented BOOLEAN NOT NULL DEFAULT FALSE,
+        minted BOOLEAN NOT NULL DEFAULT FALSE,
+        sessions_attended BIGINT NOT NULL DEFAULT 0,
+        deposit_paid BIGINT NOT NULL DEFAULT 0,
+        answers TEXT NOT NULL DEFAULT '[]',
+        registered_at BIGINT,
+        cancelled_at BIGINT,
+        slot BIGINT NOT NULL DEFAULT 0,
+        closed BOOLEAN NOT NULL DEFAULT FALSE
+    )",
+    "CREATE INDEX IF NOT EXISTS registrations_event ON registrations (event)",
+    "CREATE INDEX IF NOT EXISTS registrations_attentee ON registrations (attentee)",
+    "CREATE TABLE IF NOT EXISTS profiles (
+        pubkey TEXT PRIMARY KEY,
+        attentee TEXT NOT NULL UNIQUE,
+        display_name TEXT NOT NULL,
+        contact_hash TEXT NOT NULL,
+        social_handle TEXT NOT NULL,
+        preferences BIGINT NOT NULL,
+        slot BIGINT NOT NULL,
+        closed BOOLEAN NOT NULL DEFAULT FALSE
+    )",
+    "CREATE TABLE IF NOT EXISTS organizers (
+        pubkey TEXT PRIMARY KEY,
+        organizer TEXT NOT NULL UNIQUE,
+        events_hosted BIGINT NOT NULL,
+        events_completed BIGINT NOT NULL,
+        events_cancelled BIGINT NOT NULL,
+        total_registrations BIGINT NOT NULL,
+        total_checked_in BIGINT NOT NULL,
+        check_in_rate_bps BIGINT,
+        slot BIGINT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS check_ins (
+        registration TEXT NOT NULL,
+        session_id BIGINT NOT NULL,
+        event TEXT NOT NULL,
+        attentee TEXT NOT NULL,
+        signature TEXT NOT NULL,
+        timestamp BIGINT NOT NULL,
+        PRIMARY KEY (registration, session_id)
+    )",
+    "CREATE INDEX IF NOT EXISTS check_ins_event ON check_ins (event)",
+    "CREATE TABLE IF NOT EXISTS mints (
+        mint TEXT PRIMARY KEY,
+        event TEXT NOT NULL,
+        attentee TEXT NOT NULL,
+        registration TEXT NOT NULL,
+        signature TEXT NOT NULL,
+        timestamp BIGINT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS mints_event ON mints (event)",
+    "CREATE TABLE IF NOT EXISTS cursor (
+        id BIGINT PRIMARY KEY,
+        signature TEXT NOT NULL
+    )",
+];
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct EventRow {
+    pub pubkey: String,
+    pub creator: String,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_attentees: i64,
+    pub registered_attentees: i64,
+    pub checked_in_attentees: i64,
+    pub minted_attentees: i64,
+    pub cancelled_registrations: i64,
+    pub waitlisted_attentees: i64,
+    pub cancelled: bool,
+    pub collection_mint: String,
+    pub series: Option<String>,
+    pub slot: i64,
+    pub closed: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RegistrationRow {
+    pub pubkey: String,
+    pub event: String,
+    pub attentee: String,
+    pub payer: Option<String>,
+    pub tier: i64,
+    pub attented: bool,
+    pub minted: bool,
+    pub sessions_attended: i64,
+    pub deposit_paid: i64,
+    /// JSON array, text answers as hex sha256 hashes.
+    pub answers: String,
+    pub registered_at: Option<i64>,
+    pub cancelled_at: Option<i64>,
+    pub slot: i64,
+    pub closed: bool,
+    /// From the attentee's profile, if any.
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProfileRow {
+    pub pubkey: String,
+    pub attentee: String,
+    pub display_name: String,
+    pub contact_hash: String,
+    pub social_handle: String,
+    pub preferences: i64,
+    pub slot: i64,
+    pub closed: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct OrganizerRow {
+    pub pubkey: String,
+    pub organizer: String,
+    pub events_hosted: i64,
+    pub events_completed: i64,
+    pub events_cancelled: i64,
+    pub total_registrations: i64,
+    pub total_checked_in: i64,
+    pub check_in_rate_bps: Option<i64>,
+    pub slot: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CheckInRow {
+    pub registration: String,
+    pub session_id: i64,
+    pub event: String,
+    pub attentee: String,
+    pub signature: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MintRow {
+    pub mint: String,
+    pub event: String,
+    pub attentee: String,
+    pub registration: String,
+    pub signature: String,
+    pub timestamp: i64,
+}
+
+const EVENT_COLUMNS: &str = "pubkey, creator, name, description, url, start_time, end_time, \
+    total_attentees, registered_attentees, checked_in_attentees, minted_attentees, \
+    cancelled_registrations, waitlisted_attentees, cancelled, collection_mint, series, slot, closed";
+
+const ORGANIZER_COLUMNS: &str = "pubkey, organizer, events_hosted, events_completed, \
+    events_cancelled, total_registrations, total_checked_in, check_in_rate_bps, slot";
+
+const REGISTRATION_COLUMNS: &str = "r.pubkey, r.event, r.attentee, r.payer, r.tier, r.attented, \
+    r.minted, r.sessions_attended, r.deposit_paid, r.answers, r.registered_at, r.cancelled_at, \
+    r.slot, r.closed, p.display_name";
+
+const REGISTRATION_SOURCE: &str =
+    "registrations r LEFT JOIN profiles p ON p.attentee = r.attentee AND NOT p.closed";
+
+#[derive(Clone)]
+pub struct Store {
+    pool: AnyPool,
+}
+
+impl Store {
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self, IndexError> {
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new().connect(database_url).await?;
+        Ok(Self::new(pool))
+    }
+
+    pub async fn migrate(&self) -> Result<(), IndexError> {
+        for statement in SCHEMA {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Account updates only overwrite rows written from an older slot, so a
+    /// late snapshot cannot roll back a newer notification.
+    pub async fn upsert_event(
+        &self,
+        pubkey: &Pubkey,
+        event: &Event,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        let series = (event.series != Pubkey::default()).then(|| event.series.to_string());
+
+        sqlx::query(
+            "INSERT INTO events (pubkey, creator, name, description, url, start_time, end_time,
+                total_attentees, registered_attentees, checked_in_attentees, minted_attentees,
+                cancelled_registrations, waitlisted_attentees, cancelled, collection_mint, series,
+                slot, closed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                FALSE)
+            ON CONFLICT (pubkey) DO UPDATE SET
+                creator = excluded.creator,
+                name = excluded.name,
+                description = excluded.description,
+                url = excluded.url,
+                start_time = excluded.start_time,
+                end_time = excluded.end_time,
+                total_attentees = excluded.total_attentees,
+                registered_attentees = excluded.registered_attentees,
+                checked_in_attentees = excluded.checked_in_attentees,
+                minted_attentees = excluded.minted_attentees,
+                cancelled_registrations = excluded.cancelled_registrations,
+                waitlisted_attentees = excluded.waitlisted_attentees,
+                cancelled = excluded.cancelled,
+                collection_mint = excluded.collection_mint,
+                series = excluded.series,
+                slot = excluded.slot,
+                closed = FALSE
+            WHERE events.slot <= excluded.slot",
+        )
+        .bind(pubkey.to_string())
+        .bind(event.creator.to_string())
+        .bind(event.name.clone())
+        .bind(event.description.clone())
+        .bind(event.url.clone())
+        .bind(event.start_time)
+        .bind(event.end_time)
+        // counters are u64 on chain, far beyond any real event
+        .bind(event.total_attentees as i64)
+        .bind(event.registered_attentees as i64)
+        .bind(event.checked_in_attentees as i64)
+        .bind(event.minted_attentees as i64)
+        .bind(event.cancelled_registrations as i64)
+        .bind(event.waitlisted_attentees as i64)
+        .bind(event.cancelled)
+        .bind(event.collection_mint.to_string())
+        .bind(series)
+        .bind(slot as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_registration(
+        &self,
+        pubkey: &Pubkey,
+        registration: &EventRegistration,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        sqlx::query(
+            "INSERT INTO registrations (pubkey, event, attentee, payer, tier, attented, minted,
+                sessions_attended, deposit_paid, answers, slot, closed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, FALSE)
+            ON CONFLICT (pubkey) DO UPDATE SET
+                event = excluded.event,
+                attentee = excluded.attentee,
+                payer = excluded.payer,
+                tier = excluded.tier,
+                attented = excluded.attented,
+                minted = excluded.minted,
+                sessions_attended = excluded.sessions_attended,
+                deposit_paid = excluded.deposit_paid,
+                answers = excluded.answers,
+                slot = excluded.slot,
+                closed = FALSE
+            WHERE registrations.slot <= excluded.slot",
+        )
+        .bind(pubkey.to_string())
+        .bind(registration.event.to_string())
+        .bind(registration.attentee.to_string())
+        .bind(registration.payer.to_string())
+        .bind(i64::from(registration.tier))
+        .bind(registration.attented)
+        .bind(registration.attentence_nft_minted)
+        .bind(registration.sessions_attended as i64)
+        .bind(registration.deposit_paid as i64)
+        .bind(answers_json(&registration.answers))
+        .bind(slot as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Closed accounts keep their last indexed state.
+    pub async fn mark_closed(&self, pubkey: &Pubkey, slot: u64) -> Result<(), IndexError> {
+        for table in ["events", "registrations", "profiles"] {
+            sqlx::query(&format!(
+                "UPDATE {table} SET closed = TRUE, slot = $2 WHERE pubkey = $1 AND slot <= $2"
+            ))
+            .bind(pubkey.to_string())
+            .bind(slot as i64)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn upsert_profile(
+        &self,
+        pubkey: &Pubkey,
+        profile: &AttendeeProfile,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        sqlx::query(
+            "INSERT INTO profiles (pubkey, attentee, display_name, contact_hash, social_handle,
+                preferences, slot, closed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, FALSE)
+            ON CONFLICT (pubkey) DO UPDATE SET
+                display_name = excluded.display_name,
+                contact_hash = excluded.contact_hash,
+                social_handle = excluded.social_handle,
+                preferences = excluded.preferences,
+                slot = excluded.slot,
+                closed = FALSE
+            WHERE profiles.slot <= excluded.slot",
+        )
+        .bind(pubkey.to_string())
+        .bind(profile.attentee.to_string())
+        .bind(profile.display_name.clone())
+        .bind(hex(&profile.contact_hash))
+        .bind(profile.social_handle.clone())
+        .bind(i64::from(profile.preferences))
+        .bind(slot as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_organizer(
+        &self,
+        pubkey: &Pubkey,
+        organizer: &OrganizerProfile,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        sqlx::query(
+            "INSERT INTO organizers (pubkey, organizer, events_hosted, events_completed,
+                events_cancelled, total_registrations, total_checked_in, check_in_rate_bps, slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (pubkey) DO UPDATE SET
+                events_hosted = excluded.events_hosted,
+                events_completed = excluded.events_completed,
+                events_cancelled = excluded.events_cancelled,
+                total_registrations = excluded.total_registrations,
+                total_checked_in = excluded.total_checked_in,
+                check_in_rate_bps = excluded.check_in_rate_bps,
+                slot = excluded.slot
+            WHERE organizers.slot <= excluded.slot",
+        )
+        .bind(pubkey.to_string())
+        .bind(organizer.organizer.to_string())
+        .bind(organizer.events_hosted as i64)
+        .bind(organizer.events_completed as i64)
+        .bind(organizer.events_cancelled as i64)
+        .bind(organizer.total_registrations as i64)
+        .bind(organizer.total_checked_in as i64)
+        .bind(organizer.check_in_rate_bps().map(i64::from))
+        .bind(slot as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records an event from a transaction log. Replaying the same
+    /// transaction is a no-op, and registration changes older than the
+    /// stored slot are ignored like stale account updates.
+    pub async fn apply_event(
+        &self,
+        signature: &str,
+        event: &ProgramEvent,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        match event {
+            // event rows are written from account updates, which carry
+            // everything the creation event does
+            ProgramEvent::Created(_) => {}
+            // a registration closed on cancel can be opened again at the
+            // same address, which starts it over
+            ProgramEvent::Registered(registered) => {
+                sqlx::query(
+                    "INSERT INTO registrations (pubkey, event, attentee, tier, registered_at, slot)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (pubkey) DO UPDATE SET
+                        registered_at = excluded.registered_at,
+                        cancelled_at = NULL,
+                        closed = FALSE,
+                        slot = excluded.slot
+                    WHERE registrations.slot <= excluded.slot",
+                )
+                .bind(registered.registration.to_string())
+                .bind(registered.event.to_string())
+                .bind(registered.attentee.to_string())
+                .bind(i64::from(registered.tier))
+                .bind(registered.timestamp)
+                .bind(slot as i64)
+                .execute(&self.pool)
+                .await?;
+            }
+            ProgramEvent::Cancelled(cancelled) => {
+                sqlx::query(
+                    "UPDATE registrations SET cancelled_at = $2, closed = TRUE, slot = $3
+                    WHERE pubkey = $1 AND slot <= $3",
+                )
+                .bind(cancelled.registration.to_string())
+                .bind(cancelled.timestamp)
+                .bind(slot as i64)
+                .execute(&self.pool)
+                .await?;
+            }
+            ProgramEvent::CheckedIn(checked_in) => {
+                sqlx::query(
+                    "INSERT INTO check_ins (registration, session_id, event, attentee, signature, timestamp)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (registration, session_id) DO NOTHING",
+                )
+                .bind(checked_in.registration.to_string())
+                .bind(checked_in.session_id.map_or(-1, i64::from))
+                .bind(checked_in.event.to_string())
+                .bind(checked_in.attentee.to_string())
+                .bind(signature.to_string())
+                .bind(checked_in.timestamp)
+                .execute(&self.pool)
+                .await?;
+            }
+            ProgramEvent::Minted(minted) => {
+                sqlx::query(
+                    "INSERT INTO mints (mint, event, attentee, registration, signature, timestamp)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (mint) DO NOTHING",
+                )
+                .bind(minted.mint.to_string())
+                .bind(minted.event.to_string())
+                .bind(minted.attentee.to_string())
+                .bind(minted.registration.to_string())
+                .bind(signature.to_string())
+                .bind(minted.timestamp)
+                .execute(&self.pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Newest transaction already replayed.
+    pub async fn cursor(&self) -> Result<Option<String>, IndexError> {
+        Ok(
+            sqlx::query_scalar("SELECT signature FROM cursor WHERE id = 0")
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    pub async fn set_cursor(&self, signature: &str) -> Result<(), IndexError> {
+        sqlx::query(
+            "INSERT INTO cursor (id, signature) VALUES (0, $1)
+            ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+        )
+        .bind(signature.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn events(&self, creator: Option<&Pubkey>) -> Result<Vec<EventRow>, IndexError> {
+        let rows = match creator {
+            Some(creator) => {
+                sqlx::query_as(&format!(
+                    "SELECT {EVENT_COLUMNS} FROM events WHERE creator = $1 ORDER BY start_time"
+                ))
+                .bind(creator.to_string())
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as(&format!(
+                    "SELECT {EVENT_COLUMNS} FROM events ORDER BY start_time"
+                ))
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(rows)
+    }
+
+    pub async fn event(&self, pubkey: &Pubkey) -> Result<Option<EventRow>, IndexError> {
+        Ok(sqlx::query_as(&format!(
+            "SELECT {EVENT_COLUMNS} FROM events WHERE pubkey = $1"
+        ))
+        .bind(pubkey.to_string())
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    pub async fn event_registrations(
+        &self,
+        event: &Pubkey,
+    ) -> Result<Vec<RegistrationRow>, IndexError> {
+        Ok(sqlx::query_as(&format!(
+            "SELECT {REGISTRATION_COLUMNS} FROM {REGISTRATION_SOURCE} WHERE r.event = $1 ORDER BY r.registered_at"
+        ))
+        .bind(event.to_string())
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn attentee_registrations(
+        &self,
+        attentee: &Pubkey,
+    ) -> Result<Vec<RegistrationRow>, IndexError> {
+        Ok(sqlx::query_as(&format!(
+            "SELECT {REGISTRATION_COLUMNS} FROM {REGISTRATION_SOURCE} WHERE r.attentee = $1 ORDER BY r.registered_at"
+        ))
+        .bind(attentee.to_string())
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn profile(&self, attentee: &Pubkey) -> Result<Option<ProfileRow>, IndexError> {
+        Ok(sqlx::query_as(
+            "SELECT pubkey, attentee, display_name, contact_hash, social_handle, preferences,
+                slot, closed
+            FROM profiles WHERE attentee = $1",
+        )
+        .bind(attentee.to_string())
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Organizers with the most events hosted first.
+    pub async fn organizers(&self) -> Result<Vec<OrganizerRow>, IndexError> {
+        Ok(sqlx::query_as(&format!(
+            "SELECT {ORGANIZER_COLUMNS} FROM organizers ORDER BY events_hosted DESC"
+        ))
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn organizer(&self, organizer: &Pubkey) -> Result<Option<OrganizerRow>, IndexError> {
+        Ok(sqlx::query_as(&format!(
+            "SELECT {ORGANIZER_COLUMNS} FROM organizers WHERE organizer = $1"
+        ))
+        .bind(organizer.to_string())
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    pub async fn check_ins(&self, event: &Pubkey) -> Result<Vec<CheckInRow>, IndexError> {
+        Ok(sqlx::query_as(
+            "SELECT registration, session_id, event, attentee, signature, timestamp
+            FROM check_ins WHERE event = $1 ORDER BY timestamp",
+        )
+        .bind(event.to_string())
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn mints(&self, event: &Pubkey) -> Result<Vec<MintRow>, IndexError> {
+        Ok(sqlx::query_as(
+            "SELECT mint, event, attentee, registration, signature, timestamp
+            FROM mints WHERE event = $1 ORDER BY timestamp",
+        )
+        .bind(event.to_string())
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn answers_json(answers: &[RegistrationAnswer]) -> String {
+    let answers: Vec<_> = answers
+        .iter()
+        .map(|answer| match answer {
+            RegistrationAnswer::Skipped => serde_json::json!({ "kind": "skipped" }),
+            RegistrationAnswer::TextHash(hash) => {
+                serde_json::json!({ "kind": "text_hash", "value": hex(hash) })
+            }
+            RegistrationAnswer::Choice(choice) => {
+                serde_json::json!({ "kind": "choice", "value": choice })
+            }
+            RegistrationAnswer::Number(number) => {
+                serde_json::json!({ "kind": "number", "value": number })
+            }
+        })
+        .collect();
+
+    serde_json::Value::Array(answers).to_string()
+}
@@ -0,0 +1,98 @@
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use counter::{
+    AttendeeCheckedIn, AttendeeProfile, AttendeeRegistered, BadgeMinted, Event, EventCreated,
+    EventRegistration, OrganizerProfile, RegistrationCancelled,
+};
+
+/// Program accounts the indexer materializes.
+pub enum ProgramAccount {
+    Event(Box<Event>),
+    Registration(EventRegistration),
+    Profile(AttendeeProfile),
+    Organizer(OrganizerProfile),
+}
+
+/// Anchor events emitted by the program.
+pub enum ProgramEvent {
+    Created(EventCreated),
+    Registered(AttendeeRegistered),
+    Cancelled(RegistrationCancelled),
+    CheckedIn(AttendeeCheckedIn),
+    Minted(BadgeMinted),
+}
+
+pub fn decode_account(data: &[u8]) -> Option<ProgramAccount> {
+    if data.starts_with(Event::DISCRIMINATOR) {
+        Event::try_deserialize(&mut &data[..])
+            .ok()
+            .map(|event| ProgramAccount::Event(Box::new(event)))
+    } else if data.starts_with(EventRegistration::DISCRIMINATOR) {
+        EventRegistration::try_deserialize(&mut &data[..])
+            .ok()
+            .map(ProgramAccount::Registration)
+    } else if data.starts_with(AttendeeProfile::DISCRIMINATOR) {
+        AttendeeProfile::try_deserialize(&mut &data[..])
+            .ok()
+            .map(ProgramAccount::Profile)
+    } else if data.starts_with(OrganizerProfile::DISCRIMINATOR) {
+        OrganizerProfile::try_deserialize(&mut &data[..])
+            .ok()
+            .map(ProgramAccount::Organizer)
+    } else {
+        None
+    }
+}
+
+pub fn decode_event(data: &[u8]) -> Option<ProgramEvent> {
+    fn parse<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+        let mut body = data.strip_prefix(T::DISCRIMINATOR)?;
+        T::deserialize(&mut body).ok()
+    }
+
+    parse(data)
+        .map(ProgramEvent::Created)
+        .or_else(|| parse(data).map(ProgramEvent::Registered))
+        .or_else(|| parse(data).map(ProgramEvent::Cancelled))
+        .or_else(|| parse(data).map(ProgramEvent::CheckedIn))
+        .or_else(|| parse(data).map(ProgramEvent::Minted))
+}
+
+/// Collects the events emitted by the program from a transaction's logs.
+/// `Program data:` lines are only decoded while the program is the innermost
+/// invocation, so another program cannot forge events by logging the same
+/// bytes.
+pub fn decode_logs(logs: &[String]) -> Vec<ProgramEvent> {
+    let program_id = counter::ID.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let Some(rest) = log.strip_prefix("Program ") else {
+            continue;
+        };
+
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if invocations.last() == Some(&program_id.as_str()) {
+                events.extend(
+                    STANDARD
+                        .decode(data)
+                        .ok()
+                        .and_then(|data| decode_event(&data)),
+                );
+            }
+            continue;
+        }
+
+        let mut parts = rest.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(program), Some("invoke")) => invocations.push(program),
+            (Some(_), Some("success" | "failed:")) => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
@@ -0,0 +1,54 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use solana_client::{client_error::ClientError, nonblocking::pubsub_client::PubsubClientError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexError {
+    #[error("invalid config: {0}")]
+    Config(String),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("rpc error: {0}")]
+    Rpc(#[from] Box<ClientError>),
+    #[error("subscription error: {0}")]
+    Pubsub(#[from] Box<PubsubClientError>),
+    #[error("invalid public key {0}")]
+    InvalidKey(String),
+    #[error("invalid signature {0}")]
+    InvalidSignature(String),
+    #[error("subscription closed")]
+    SubscriptionClosed,
+    #[error("not found")]
+    NotFound,
+}
+
+impl From<ClientError> for IndexError {
+    fn from(err: ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+impl From<PubsubClientError> for IndexError {
+    fn from(err: PubsubClientError) -> Self {
+        Self::Pubsub(Box::new(err))
+    }
+}
+
+impl IntoResponse for IndexError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::InvalidKey(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (
+            status,
+            Json(serde_json::json!({ "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+use futures::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig,
+        RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+    },
+};
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::{
+    db::Store,
+    decode::{decode_account, decode_logs, ProgramAccount},
+    error::IndexError,
+};
+
+pub struct Indexer {
+    store: Store,
+    rpc: RpcClient,
+    ws_url: String,
+}
+
+impl Indexer {
+    pub fn new(store: Store, rpc_url: String, ws_url: String) -> Self {
+        Self {
+            store,
+            rpc: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            ws_url,
+        }
+    }
+
+    /// Snapshots the program accounts, optionally replays the transactions
+    /// missed since the last run, then follows account and log
+    /// notifications until the websocket closes.
+    pub async fn run(&self, replay: bool) -> Result<(), IndexError> {
+        let pubsub = PubsubClient::new(&self.ws_url).await?;
+
+        // subscribe before the snapshot so nothing lands in between
+        let (mut accounts, _accounts_unsubscribe) = pubsub
+            .program_subscribe(&counter::ID, Some(program_accounts_config()))
+            .await?;
+        let (mut logs, _logs_unsubscribe) = pubsub
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![counter::ID.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+
+        self.snapshot().await?;
+
+        if replay {
+            self.replay().await?;
+        }
+
+        loop {
+            tokio::select! {
+                Some(update) = accounts.next() => {
+                    let pubkey = Pubkey::from_str(&update.value.pubkey)
+                        .map_err(|_| IndexError::InvalidKey(update.value.pubkey.clone()))?;
+
+                    match update.value.account.decode::<Account>() {
+                        Some(account) => self.apply_account(&pubkey, &account, update.context.slot).await?,
+                        None => tracing::warn!(%pubkey, "could not decode account update"),
+                    }
+                }
+                Some(update) = logs.next() => {
+                    if update.value.err.is_none() {
+                        self.apply_logs(&update.value.signature, &update.value.logs, update.context.slot).await?;
+                        // a restart with `--replay` resumes after the last live transaction
+                        self.store.set_cursor(&update.value.signature).await?;
+                    }
+                }
+                else => return Err(IndexError::SubscriptionClosed),
+            }
+        }
+    }
+
+    /// Writes the current state of every program account.
+    pub async fn snapshot(&self) -> Result<(), IndexError> {
+        // the slot is read first, so any notification for a later change wins
+        let slot = self.rpc.get_slot().await?;
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&counter::ID, program_accounts_config())
+            .await?;
+
+        for (pubkey, account) in &accounts {
+            self.apply_account(pubkey, account, slot).await?;
+        }
+
+        tracing::info!(slot, accounts = accounts.len(), "indexed program accounts");
+        Ok(())
+    }
+
+    /// Replays the logs of every successful program transaction newer than
+    /// the stored cursor, oldest first.
+    pub async fn replay(&self) -> Result<(), IndexError> {
+        let until = self
+            .store
+            .cursor()
+            .await?
+            .map(|cursor| parse_signature(&cursor))
+            .transpose()?;
+        let mut before = None;
+        let mut signatures = Vec::new();
+
+        loop {
+            let page = self
+                .rpc
+                .get_signatures_for_address_with_config(
+                    &counter::ID,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: None,
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await?;
+
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(parse_signature(&last.signature)?);
+
+            signatures.extend(
+                page.into_iter()
+                    .filter(|status| status.err.is_none())
+                    .map(|status| status.signature),
+            );
+        }
+
+        for signature in signatures.iter().rev() {
+            let transaction = self
+                .rpc
+                .get_transaction_with_config(
+                    &parse_signature(signature)?,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await?;
+
+            let slot = transaction.slot;
+            if let Some(OptionSerializer::Some(logs)) =
+                transaction.transaction.meta.map(|meta| meta.log_messages)
+            {
+                self.apply_logs(signature, &logs, slot).await?;
+            }
+
+            self.store.set_cursor(signature).await?;
+        }
+
+        tracing::info!(
+            transactions = signatures.len(),
+            "replayed program transactions"
+        );
+        Ok(())
+    }
+
+    async fn apply_account(
+        &self,
+        pubkey: &Pubkey,
+        account: &Account,
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        if account.lamports == 0 || account.owner != counter::ID {
+            return self.store.mark_closed(pubkey, slot).await;
+        }
+
+        match decode_account(&account.data) {
+            Some(ProgramAccount::Event(event)) => {
+                self.store.upsert_event(pubkey, &event, slot).await
+            }
+            Some(ProgramAccount::Registration(registration)) => {
+                self.store
+                    .upsert_registration(pubkey, &registration, slot)
+                    .await
+            }
+            Some(ProgramAccount::Profile(profile)) => {
+                self.store.upsert_profile(pubkey, &profile, slot).await
+            }
+            Some(ProgramAccount::Organizer(organizer)) => {
+                self.store.upsert_organizer(pubkey, &organizer, slot).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn apply_logs(
+        &self,
+        signature: &str,
+        logs: &[String],
+        slot: u64,
+    ) -> Result<(), IndexError> {
+        for event in decode_logs(logs) {
+            self.store.apply_event(signature, &event, slot).await?;
+        }
+        Ok(())
+    }
+}
+
+fn program_accounts_config() -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn parse_signature(signature: &str) -> Result<Signature, IndexError> {
+    Signature::from_str(signature).map_err(|_| IndexError::InvalidSignature(signature.to_string()))
+}
@@ -0,0 +1,13 @@
+//! Indexer for the event program.
+//!
+//! Materializes `Event` and `EventRegistration` accounts, plus the check-ins
+//! and badge mints emitted as Anchor events, into SQLite or Postgres and
+//! serves them over a small JSON API, so dashboards don't have to scan
+//! program accounts.
+
+pub mod api;
+pub mod config;
+pub mod db;
+pub mod decode;
+pub mod error;
+pub mod ingest;
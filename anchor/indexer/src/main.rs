@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use indexer::{api, config::Config, db::Store, ingest::Indexer};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_env()?;
+    let store = Store::connect(&config.database_url).await?;
+    store.migrate().await?;
+
+    let indexer = Indexer::new(store.clone(), config.rpc_url, config.ws_url);
+
+    let listener = tokio::net::TcpListener::bind(&config.bind_addr).await?;
+    tracing::info!(bind_addr = %config.bind_addr, "indexer api listening");
+
+    tokio::select! {
+        result = indexer.run(config.replay) => result?,
+        result = axum::serve(listener, api::router(Arc::new(store))) => result?,
+    }
+    Ok(())
+}
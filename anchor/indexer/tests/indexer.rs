@@ -0,0 +1,280 @@
+use anchor_lang::Event as _;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use counter::{
+    AttendeeCheckedIn, AttendeeProfile, AttendeeRegistered, BadgeMinted, EventRegistration,
+    OrganizerProfile, RegistrationAnswer, RegistrationCancelled,
+};
+use indexer::{
+    db::Store,
+    decode::{decode_logs, ProgramEvent},
+};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::any::AnyPoolOptions;
+
+async fn store() -> Store {
+    sqlx::any::install_default_drivers();
+    // a single connection, every in-memory SQLite connection is its own database
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    let store = Store::new(pool);
+    store.migrate().await.unwrap();
+    store
+}
+
+fn program_data(event: &impl anchor_lang::Event) -> String {
+    format!("Program data: {}", STANDARD.encode(event.data()))
+}
+
+fn registration(event: Pubkey, attentee: Pubkey) -> EventRegistration {
+    EventRegistration {
+        event,
+        attentee,
+        payer: attentee,
+        tier: 0,
+        registered: true,
+        attented: false,
+        attentence_nft_minted: false,
+        sessions_attended: 0,
+        series_attendance_recorded: false,
+        deposit_paid: 0,
+        failed_code_attempts: 0,
+        code_locked_until: 0,
+        answers: vec![RegistrationAnswer::Choice(1), RegistrationAnswer::Skipped],
+        bump: 255,
+    }
+}
+
+#[test]
+fn decodes_only_events_logged_by_the_program() {
+    let other_program = Pubkey::new_unique();
+    let minted = BadgeMinted {
+        event: Pubkey::new_unique(),
+        attentee: Pubkey::new_unique(),
+        registration: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        timestamp: 1_700_000_000,
+    };
+
+    let logs = vec![
+        format!("Program {} invoke [1]", counter::ID),
+        "Program log: Instruction: MintNft".to_string(),
+        format!("Program {other_program} invoke [2]"),
+        program_data(&minted),
+        format!("Program {other_program} success"),
+        program_data(&minted),
+        format!(
+            "Program {} consumed 52000 of 400000 compute units",
+            counter::ID
+        ),
+        format!("Program {} success", counter::ID),
+        program_data(&minted),
+    ];
+
+    let events = decode_logs(&logs);
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], ProgramEvent::Minted(event) if event.mint == minted.mint));
+}
+
+#[tokio::test]
+async fn keeps_the_newest_account_state() {
+    let store = store().await;
+    let event = Pubkey::new_unique();
+    let attentee = Pubkey::new_unique();
+    let pubkey = Pubkey::new_unique();
+
+    let mut checked_in = registration(event, attentee);
+    checked_in.attented = true;
+    checked_in.sessions_attended = 1 << 63;
+
+    store
+        .upsert_registration(&pubkey, &checked_in, 20)
+        .await
+        .unwrap();
+    store
+        .upsert_registration(&pubkey, &registration(event, attentee), 10)
+        .await
+        .unwrap();
+
+    let rows = store.event_registrations(&event).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].attented);
+    assert_eq!(rows[0].sessions_attended as u64, 1 << 63);
+
+    assert_eq!(
+        rows[0].answers,
+        r#"[{"kind":"choice","value":1},{"kind":"skipped"}]"#
+    );
+
+    store.mark_closed(&pubkey, 30).await.unwrap();
+    assert!(store.attentee_registrations(&attentee).await.unwrap()[0].closed);
+}
+
+#[tokio::test]
+async fn records_log_events_once() {
+    let store = store().await;
+    let event = Pubkey::new_unique();
+    let attentee = Pubkey::new_unique();
+    let registration_key = Pubkey::new_unique();
+
+    let checked_in = ProgramEvent::CheckedIn(AttendeeCheckedIn {
+        event,
+        attentee,
+        registration: registration_key,
+        session_id: Some(3),
+        timestamp: 1_700_000_000,
+    });
+    let minted = ProgramEvent::Minted(BadgeMinted {
+        event,
+        attentee,
+        registration: registration_key,
+        mint: Pubkey::new_unique(),
+        timestamp: 1_700_000_100,
+    });
+
+    for signature in ["first", "replayed"] {
+        store.apply_event(signature, &checked_in, 5).await.unwrap();
+        store.apply_event(signature, &minted, 5).await.unwrap();
+    }
+
+    let check_ins = store.check_ins(&event).await.unwrap();
+    assert_eq!(check_ins.len(), 1);
+    assert_eq!(check_ins[0].session_id, 3);
+    assert_eq!(check_ins[0].signature, "first");
+    assert_eq!(store.mints(&event).await.unwrap().len(), 1);
+
+    store
+        .upsert_registration(&registration_key, &registration(event, attentee), 5)
+        .await
+        .unwrap();
+    store
+        .apply_event(
+            "cancel",
+            &ProgramEvent::Cancelled(RegistrationCancelled {
+                event,
+                attentee,
+                registration: registration_key,
+                timestamp: 1_700_000_200,
+            }),
+            6,
+        )
+        .await
+        .unwrap();
+
+    let rows = store.event_registrations(&event).await.unwrap();
+    assert!(rows[0].closed);
+    assert_eq!(rows[0].cancelled_at, Some(1_700_000_200));
+}
+
+#[tokio::test]
+async fn reopens_registrations_and_ignores_stale_logs() {
+    let store = store().await;
+    let event = Pubkey::new_unique();
+    let attentee = Pubkey::new_unique();
+    let registration_key = Pubkey::new_unique();
+
+    let registered = |timestamp| {
+        ProgramEvent::Registered(AttendeeRegistered {
+            event,
+            attentee,
+            registration: registration_key,
+            tier: 0,
+            timestamp,
+        })
+    };
+    let cancelled = ProgramEvent::Cancelled(RegistrationCancelled {
+        event,
+        attentee,
+        registration: registration_key,
+        timestamp: 1_700_000_100,
+    });
+
+    store
+        .apply_event("register", &registered(1_700_000_000), 10)
+        .await
+        .unwrap();
+    store.apply_event("cancel", &cancelled, 20).await.unwrap();
+    store
+        .apply_event("register again", &registered(1_700_000_200), 30)
+        .await
+        .unwrap();
+
+    let rows = store.event_registrations(&event).await.unwrap();
+    assert!(!rows[0].closed);
+    assert_eq!(rows[0].registered_at, Some(1_700_000_200));
+    assert_eq!(rows[0].cancelled_at, None);
+
+    // replaying the first lifetime changes nothing
+    store
+        .apply_event("register", &registered(1_700_000_000), 10)
+        .await
+        .unwrap();
+    store.apply_event("cancel", &cancelled, 20).await.unwrap();
+
+    let rows = store.event_registrations(&event).await.unwrap();
+    assert!(!rows[0].closed);
+    assert_eq!(rows[0].registered_at, Some(1_700_000_200));
+    assert_eq!(rows[0].slot, 30);
+}
+
+#[tokio::test]
+async fn joins_profiles_into_registrations() {
+    let store = store().await;
+    let event = Pubkey::new_unique();
+    let attentee = Pubkey::new_unique();
+
+    store
+        .upsert_registration(&Pubkey::new_unique(), &registration(event, attentee), 1)
+        .await
+        .unwrap();
+    store
+        .upsert_profile(
+            &Pubkey::new_unique(),
+            &AttendeeProfile {
+                attentee,
+                display_name: "Ada".to_string(),
+                contact_hash: [0xab; 32],
+                social_handle: "@ada".to_string(),
+                preferences: 1,
+                bump: 255,
+            },
+            1,
+        )
+        .await
+        .unwrap();
+
+    let rows = store.event_registrations(&event).await.unwrap();
+    assert_eq!(rows[0].display_name.as_deref(), Some("Ada"));
+
+    let profile = store.profile(&attentee).await.unwrap().unwrap();
+    assert_eq!(profile.contact_hash, "ab".repeat(32));
+}
+
+#[tokio::test]
+async fn stores_organizer_check_in_rate() {
+    let store = store().await;
+    let organizer = Pubkey::new_unique();
+    let profile = OrganizerProfile {
+        organizer,
+        events_hosted: 3,
+        events_completed: 2,
+        events_cancelled: 1,
+        total_registrations: 40,
+        total_checked_in: 30,
+        bump: 255,
+    };
+
+    store
+        .upsert_organizer(&Pubkey::new_unique(), &profile, 1)
+        .await
+        .unwrap();
+
+    let row = store.organizer(&organizer).await.unwrap().unwrap();
+    assert_eq!(row.events_cancelled, 1);
+    assert_eq!(row.check_in_rate_bps, Some(7_500));
+    assert_eq!(store.organizers().await.unwrap().len(), 1);
+}
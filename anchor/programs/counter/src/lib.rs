@@ -1,17 +1,32 @@
 #![allow(clippy::result_large_err)]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    hash::{hash, hashv},
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use anchor_lang::system_program::{create_account, transfer, CreateAccount, Transfer};
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{
+        create as create_associated_token_account, AssociatedToken,
+        Create as CreateAssociatedTokenAccount,
+    },
     metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata},
-    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
+    token::spl_token::state::Mint as SplMint,
+    token_interface::{
+        close_account, initialize_mint2, mint_to, transfer_checked, CloseAccount, InitializeMint2,
+        Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 use anchor_spl::metadata::{
-    create_master_edition_v3,
+    approve_collection_authority, create_master_edition_v3,
     mpl_token_metadata::types::{CollectionDetails, Creator, DataV2},
-    set_and_verify_sized_collection_item, sign_metadata, CreateMasterEditionV3,
-    SetAndVerifySizedCollectionItem, SignMetadata,
+    set_and_verify_sized_collection_item, sign_metadata, update_metadata_accounts_v2,
+    ApproveCollectionAuthority, CreateMasterEditionV3, SetAndVerifySizedCollectionItem,
+    SignMetadata, UpdateMetadataAccountsV2,
 };
 
 // Count3AcZucFDPSFBAeHkQ6AvttieKUkyJ8HiQGhQwe
@@ -22,10 +37,122 @@ pub const ANCHOR_DISCRIMINATOR_SIZE: usize = 8;
 #[constant]
 pub const SYMBOL: &str = "TLT";
 
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_CREATORS: usize = 5;
+
+#[constant]
+pub const MAX_SESSIONS: u8 = 64;
+
+/// Time after `end_time` before anyone can crank an event's accounts closed.
+#[constant]
+pub const CLEANUP_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// Paid out of the event account's rent to whoever cranks it closed.
+#[constant]
+pub const CLEANUP_BOUNTY_LAMPORTS: u64 = 100_000;
+
+pub const AIRDROP_ACCOUNTS_PER_BADGE: usize = 6;
+
+/// Precision of attested check-in locations, roughly 1.2 x 0.6 km cells.
+#[constant]
+pub const ATTESTED_GEOHASH_LENGTH: usize = 6;
+
+/// How old a location attestation may be when it is used to check in.
+#[constant]
+pub const ATTESTATION_MAX_AGE: i64 = 5 * 60;
+
+const GEOHASH_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[constant]
+pub const MAX_REGISTRATION_QUESTIONS: usize = 4;
+pub const MAX_QUESTION_LENGTH: usize = 64;
+pub const MAX_PROFILE_FIELD_LENGTH: usize = 32;
+pub const MAX_TIER_NAME_LENGTH: usize = 16;
+pub const MAX_TIER_URI_LENGTH: usize = 64;
+
+/// Wrong attendance codes a registration may submit before it is locked out.
+#[constant]
+pub const MAX_FAILED_CODE_ATTEMPTS: u8 = 5;
+
+/// How long a registration stays locked out after too many wrong codes.
+#[constant]
+pub const CODE_LOCKOUT_PERIOD: i64 = 10 * 60;
+
+/// Most single-use claim codes an event can issue, a 2 KiB spent bitmap.
+#[constant]
+pub const MAX_CLAIM_CODES: u32 = 16_384;
+
+/// Shortest rotation period for attendance codes, in seconds.
+#[constant]
+pub const MIN_CODE_ROTATION_PERIOD: u32 = 10;
+
+/// Most code windows an event may span. Verifying a rotating code hashes
+/// back at most this many times, which keeps check-in under the compute
+/// budget.
+#[constant]
+pub const MAX_CODE_WINDOWS: u32 = 256;
+
+/// `msg!` that only costs compute units in builds with the `debug-logs`
+/// feature.
+macro_rules! debug_msg {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "debug-logs")]
+        msg!($($arg)*);
+    };
+}
+
+/// Highest platform fee the admin can set, 10% of the ticket price.
+#[constant]
+pub const MAX_PLATFORM_FEE_BASIS_POINTS: u16 = 1_000;
+
 #[program]
 pub mod counter {
     use super::*;
 
+    /// Creates the program config. Only the upgrade authority can call it, so
+    /// nobody can claim the admin role between deploy and setup.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        max_event_size: u64,
+    ) -> Result<()> {
+        *ctx.accounts.config = Config {
+            admin: ctx.accounts.admin.key(),
+            fee_basis_points,
+            treasury,
+            paused: false,
+            max_event_size,
+            bump: ctx.bumps.config,
+        };
+        ctx.accounts.config.validate()
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Pubkey,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        paused: bool,
+        max_event_size: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.fee_basis_points = fee_basis_points;
+        config.treasury = treasury;
+        config.paused = paused;
+        config.max_event_size = max_event_size;
+        config.validate()
+    }
+
+    /// Emergency stop for registrations, mints and payouts. Refunds and
+    /// cancellations keep working while paused.
+    pub fn set_program_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.paused = paused;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_event(
         ctx: Context<InitializeEvent>,
         name: String,
@@ -34,24 +161,66 @@ pub mod counter {
         attentance_code: [u8; 32],
         start_time: i64,
         end_time: i64,
-        total_attentees: u32,
-        collection_mint: Pubkey,
+        total_attentees: u64,
+        _collection_mint: Pubkey,
+        symbol: String,
+        seller_fee_basis_points: u16,
+        creators: Vec<EventCreator>,
     ) -> Result<()> {
         // if start_time <= end_time {
         //     return Err(ErrorCode::InvalidEventTime.into());
         // }
 
+        ctx.accounts.config.check_new_event(total_attentees)?;
+
+        let (symbol, creators) = royalty_settings(
+            ctx.accounts.payer.key(),
+            symbol,
+            seller_fee_basis_points,
+            creators,
+        )?;
+
         *ctx.accounts.event_account = Event {
             creator: *ctx.accounts.payer.key,
-            name: name,
-            description: description,
-            url: url,
-            attentance_code: attentance_code,
-            start_time: start_time,
-            end_time: end_time,
-            total_attentees: total_attentees,
+            collection_name: name.clone(),
+            name,
+            description,
+            url,
+            attentance_code,
+            start_time,
+            end_time,
+            total_attentees,
             registered_attentees: 0,
-            collection_mint: collection_mint,
+            open_registrations: 0,
+            collection_mint: ctx.accounts.collection_mint.key(),
+            session_count: 0,
+            min_sessions_for_badge: 0,
+            series: Pubkey::default(),
+            occurrence: 0,
+            deposit_lamports: 0,
+            deposit_beneficiary: Pubkey::default(),
+            deposit_claim_deadline: 0,
+            sponsor_refund_deadline: 0,
+            geofence: String::new(),
+            attestation_authority: Pubkey::default(),
+            code_rotation_period: 0,
+            code_checkpoint_window: 0,
+            failed_code_attempts: 0,
+            question_count: 0,
+            checked_in_attentees: 0,
+            minted_attentees: 0,
+            cancelled_registrations: 0,
+            waitlisted_attentees: 0,
+            cancelled: false,
+            paused: false,
+            walk_in_tier: None,
+            claim_code_count: 0,
+            bump: ctx.bumps.event_account,
+            collection_mint_bump: ctx.bumps.collection_mint,
+            vault_bump: vault_bump(&ctx.accounts.event_account.key()),
+            symbol,
+            seller_fee_basis_points,
+            creators,
         };
 
         let signer_seeds: &[&[&[u8]]] = &[&[
@@ -60,87 +229,220 @@ pub mod counter {
             &[ctx.bumps.collection_mint],
         ]];
 
-        msg!("creating mint account...");
-
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.collection_mint.to_account_info(),
-                    to: ctx.accounts.collection_token_account.to_account_info(),
-                    authority: ctx.accounts.collection_mint.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            1,
-        )?;
-
-        msg!("creating metadata account");
-
-        create_metadata_accounts_v3(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                CreateMetadataAccountsV3 {
-                    metadata: ctx.accounts.metadata.to_account_info(),
-                    mint: ctx.accounts.collection_mint.to_account_info(),
-                    mint_authority: ctx.accounts.collection_mint.to_account_info(),
-                    payer: ctx.accounts.payer.to_account_info(),
-                    update_authority: ctx.accounts.collection_mint.to_account_info(),
-                    system_program: ctx.accounts.system_program.to_account_info(),
-                    rent: ctx.accounts.rent.to_account_info(),
-                },
-                signer_seeds,
-            ),
+        CollectionNft {
+            payer: ctx.accounts.payer.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            token_account: ctx.accounts.collection_token_account.to_account_info(),
+            metadata: ctx.accounts.metadata.to_account_info(),
+            master_edition: ctx.accounts.master_edition.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .create(
             DataV2 {
                 name: ctx.accounts.event_account.name.clone(),
-                symbol: SYMBOL.to_string(),
+                symbol: ctx.accounts.event_account.symbol.clone(),
                 uri: ctx.accounts.event_account.url.clone(),
-                seller_fee_basis_points: 0,
-                creators: Some(vec![Creator {
-                    address: ctx.accounts.collection_mint.key(),
-                    verified: false,
-                    share: 100,
-                }]),
+                seller_fee_basis_points,
+                creators: Some(metadata_creators(&ctx.accounts.event_account.creators)),
                 collection: None,
                 uses: None,
             },
-            true,
-            true,
-            Some(CollectionDetails::V1 { size: 0 }),
+            signer_seeds,
         )?;
 
-        msg!("creating master edition account");
+        ctx.accounts
+            .organizer_account
+            .record_hosted(ctx.accounts.payer.key(), ctx.bumps.organizer_account)?;
 
-        create_master_edition_v3(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                CreateMasterEditionV3 {
-                    edition: ctx.accounts.master_edition.to_account_info(),
-                    mint: ctx.accounts.collection_mint.to_account_info(),
-                    update_authority: ctx.accounts.collection_mint.to_account_info(),
-                    mint_authority: ctx.accounts.collection_mint.to_account_info(),
-                    payer: ctx.accounts.payer.to_account_info(),
-                    metadata: ctx.accounts.metadata.to_account_info(),
-                    token_program: ctx.accounts.token_program.to_account_info(),
-                    system_program: ctx.accounts.system_program.to_account_info(),
-                    rent: ctx.accounts.rent.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            Some(0),
+        emit!(EventCreated {
+            event: ctx.accounts.event_account.key(),
+            creator: ctx.accounts.event_account.creator,
+            start_time,
+            end_time,
+            total_attentees,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_series(
+        ctx: Context<InitializeSeries>,
+        name: String,
+        description: String,
+        url: String,
+        symbol: String,
+        seller_fee_basis_points: u16,
+        creators: Vec<EventCreator>,
+    ) -> Result<()> {
+        let (symbol, creators) = royalty_settings(
+            ctx.accounts.payer.key(),
+            symbol,
+            seller_fee_basis_points,
+            creators,
         )?;
 
-        msg!("verifying the collection...");
+        *ctx.accounts.series_account = EventSeries {
+            creator: ctx.accounts.payer.key(),
+            name,
+            description,
+            url,
+            collection_mint: ctx.accounts.collection_mint.key(),
+            collection_mint_bump: ctx.bumps.collection_mint,
+            occurrence_count: 0,
+            symbol,
+            seller_fee_basis_points,
+            creators,
+            bump: ctx.bumps.series_account,
+        };
 
-        sign_metadata(CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            SignMetadata {
-                creator: ctx.accounts.collection_mint.to_account_info(),
-                metadata: ctx.accounts.metadata.to_account_info(),
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"series_collection_mint".as_ref(),
+            ctx.accounts.payer.key.as_ref(),
+            ctx.accounts.series_account.name.as_bytes(),
+            &[ctx.bumps.collection_mint],
+        ]];
+
+        CollectionNft {
+            payer: ctx.accounts.payer.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            token_account: ctx.accounts.collection_token_account.to_account_info(),
+            metadata: ctx.accounts.metadata.to_account_info(),
+            master_edition: ctx.accounts.master_edition.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .create(
+            DataV2 {
+                name: ctx.accounts.series_account.name.clone(),
+                symbol: ctx.accounts.series_account.symbol.clone(),
+                uri: ctx.accounts.series_account.url.clone(),
+                seller_fee_basis_points,
+                creators: Some(metadata_creators(&ctx.accounts.series_account.creators)),
+                collection: None,
+                uses: None,
             },
             signer_seeds,
-        ))?;
+        )?;
+
+        Ok(())
+    }
+
+    pub fn create_series_occurrence(
+        ctx: Context<CreateSeriesOccurrence>,
+        name: String,
+        description: String,
+        attentance_code: [u8; 32],
+        start_time: i64,
+        end_time: i64,
+        total_attentees: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.check_new_event(total_attentees)?;
+
+        let series_account = &mut ctx.accounts.series_account;
+
+        *ctx.accounts.event_account = Event {
+            creator: ctx.accounts.creator.key(),
+            name,
+            description,
+            url: series_account.url.clone(),
+            attentance_code,
+            start_time,
+            end_time,
+            total_attentees,
+            registered_attentees: 0,
+            open_registrations: 0,
+            collection_mint: series_account.collection_mint,
+            session_count: 0,
+            min_sessions_for_badge: 0,
+            collection_name: series_account.name.clone(),
+            series: series_account.key(),
+            occurrence: series_account.occurrence_count,
+            deposit_lamports: 0,
+            deposit_beneficiary: Pubkey::default(),
+            deposit_claim_deadline: 0,
+            sponsor_refund_deadline: 0,
+            geofence: String::new(),
+            attestation_authority: Pubkey::default(),
+            code_rotation_period: 0,
+            code_checkpoint_window: 0,
+            failed_code_attempts: 0,
+            question_count: 0,
+            checked_in_attentees: 0,
+            minted_attentees: 0,
+            cancelled_registrations: 0,
+            waitlisted_attentees: 0,
+            cancelled: false,
+            paused: false,
+            walk_in_tier: None,
+            claim_code_count: 0,
+            bump: ctx.bumps.event_account,
+            collection_mint_bump: series_account.collection_mint_bump,
+            vault_bump: vault_bump(&ctx.accounts.event_account.key()),
+            symbol: series_account.symbol.clone(),
+            seller_fee_basis_points: series_account.seller_fee_basis_points,
+            creators: series_account.creators.clone(),
+        };
+
+        series_account.occurrence_count = series_account
+            .occurrence_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        ctx.accounts
+            .organizer_account
+            .record_hosted(ctx.accounts.creator.key(), ctx.bumps.organizer_account)?;
+        Ok(())
+    }
+
+    pub fn record_series_attendance(ctx: Context<RecordSeriesAttendance>) -> Result<()> {
+        let registration_account = &mut ctx.accounts.registration_account;
+
+        if !registration_account.attentence_nft_minted {
+            return Err(ErrorCode::NotAttended.into());
+        }
+
+        if registration_account.series_attendance_recorded {
+            return Err(ErrorCode::SeriesAttendanceAlreadyRecorded.into());
+        }
+
+        registration_account.series_attendance_recorded = true;
+
+        let occurrence = ctx.accounts.event_account.occurrence;
+        let attendance_account = &mut ctx.accounts.attendance_account;
+
+        if attendance_account.occurrences_attended == 0 {
+            attendance_account.series = ctx.accounts.series_account.key();
+            attendance_account.attentee = ctx.accounts.attentee.key();
+            attendance_account.current_streak = 1;
+            attendance_account.last_occurrence = occurrence;
+            attendance_account.bump = ctx.bumps.attendance_account;
+        } else if occurrence > attendance_account.last_occurrence {
+            // streaks only extend on the very next occurrence, older
+            // occurrences recorded late still count towards the total
+            attendance_account.current_streak =
+                if occurrence == attendance_account.last_occurrence + 1 {
+                    attendance_account
+                        .current_streak
+                        .checked_add(1)
+                        .ok_or(ErrorCode::OverflowError)?
+                } else {
+                    1
+                };
+            attendance_account.last_occurrence = occurrence;
+        }
 
+        attendance_account.occurrences_attended = attendance_account
+            .occurrences_attended
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        attendance_account.longest_streak = attendance_account
+            .longest_streak
+            .max(attendance_account.current_streak);
         Ok(())
     }
 
@@ -150,394 +452,3542 @@ pub mod counter {
         attentance_code: [u8; 32],
         start_time: i64,
         end_time: i64,
-        total_attentees: u32,
+        total_attentees: u64,
     ) -> Result<()> {
+        if total_attentees > ctx.accounts.config.max_event_size {
+            return Err(ErrorCode::EventTooLarge.into());
+        }
+
         let event_account = &mut ctx.accounts.event_account;
+        // with rotating codes this is a new commitment, verified from scratch
         event_account.attentance_code = attentance_code;
+        event_account.code_checkpoint_window = 0;
         event_account.start_time = start_time;
         event_account.end_time = end_time;
         event_account.total_attentees = total_attentees;
-        Ok(())
+        event_account.check_code_windows()
     }
 
-    pub fn close_event(ctx: Context<CloseEvent>) -> Result<()> {
-        msg!("closing account: {:?}", ctx.accounts.event_account.key());
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_ticket_tier(
+        ctx: Context<CreateTicketTier>,
+        tier_id: u8,
+        name: String,
+        price: u64,
+        capacity: u64,
+        registration_start: i64,
+        registration_end: i64,
+        uri: String,
+    ) -> Result<()> {
+        if registration_start >= registration_end {
+            return Err(ErrorCode::InvalidTierWindow.into());
+        }
+
+        if name.len() > MAX_TIER_NAME_LENGTH || uri.len() > MAX_TIER_URI_LENGTH {
+            return Err(ErrorCode::InvalidTierMetadata.into());
+        }
+
+        *ctx.accounts.tier_account = TicketTier {
+            event: ctx.accounts.event_account.key(),
+            tier_id,
+            name,
+            price,
+            capacity,
+            registered_attentees: 0,
+            registration_start,
+            registration_end,
+            uri,
+            bump: ctx.bumps.tier_account,
+        };
         Ok(())
     }
 
-    pub fn register_event(ctx: Context<RegisterEvent>) -> Result<()> {
-        // let clock = Clock::get()?;
-
-        // if clock.slot > ctx.accounts.event_account.start_time as u64
-        //     && clock.slot < ctx.accounts.event_account.end_time as u64
-        // {
-        //     return Err(ErrorCode::RegistrationNotOpenYet.into());
-        // }
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_id: u8,
+        start_time: i64,
+        end_time: i64,
+        attentance_code: [u8; 32],
+    ) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
 
-        if ctx.accounts.event_account.registered_attentees
-            == ctx.accounts.event_account.total_attentees
-        {
-            return Err(ErrorCode::RegistrationCompleted.into());
+        if session_id != event_account.session_count || session_id >= MAX_SESSIONS {
+            return Err(ErrorCode::InvalidSessionId.into());
         }
 
-        *ctx.accounts.registration_account = EventRegistration {
-            event: ctx.accounts.event_account.key(),
-            attentee: ctx.accounts.attentee.key(),
-            registered: true,
-            attented: false,
-            attentence_nft_minted: false,
-        };
+        if start_time >= end_time {
+            return Err(ErrorCode::InvalidEventTime.into());
+        }
 
-        let event_account: &mut Account<'_, Event> = &mut ctx.accounts.event_account;
-        event_account.registered_attentees = event_account
-            .registered_attentees
+        event_account.session_count = event_account
+            .session_count
             .checked_add(1)
             .ok_or(ErrorCode::OverflowError)?;
+
+        *ctx.accounts.session_account = EventSession {
+            event: event_account.key(),
+            session_id,
+            start_time,
+            end_time,
+            attentance_code,
+            bump: ctx.bumps.session_account,
+        };
         Ok(())
     }
 
-    pub fn cancel_registration(ctx: Context<CancelRegistration>) -> Result<()> {
-        let event_account = &mut ctx.accounts.event_account;
-        event_account.registered_attentees = event_account
-            .registered_attentees
-            .checked_sub(1)
-            .ok_or(ErrorCode::OverflowError)?;
+    pub fn set_badge_requirement(
+        ctx: Context<SetBadgeRequirement>,
+        _name: String,
+        min_sessions_for_badge: u8,
+    ) -> Result<()> {
+        if min_sessions_for_badge > MAX_SESSIONS {
+            return Err(ErrorCode::InvalidSessionId.into());
+        }
+
+        ctx.accounts.event_account.min_sessions_for_badge = min_sessions_for_badge;
         Ok(())
     }
 
-    pub fn mint_nft(ctx: Context<MintNft>, attentance_code: [u8; 32]) -> Result<()> {
-        for acc in ctx.remaining_accounts.iter() {
-            msg!("Remaining account: {:?}", acc.key);
+    pub fn check_in_session(
+        ctx: Context<CheckInSession>,
+        session_id: u8,
+        attentance_code: [u8; 32],
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let session_account = &ctx.accounts.session_account;
+
+        if ctx.accounts.event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
         }
 
-        let clock = Clock::get()?;
+        // the code alone does not prove presence at a geofenced venue
+        if !ctx.accounts.event_account.geofence.is_empty() {
+            return Err(ErrorCode::AttestationRequired.into());
+        }
 
-        msg!(
-            "MintNft: event_account: {:?}",
-            ctx.accounts.event_account.key()
-        );
-        msg!("MintNft: attentee: {:?}", ctx.accounts.attentee.key());
-        msg!(
-            "MintNft: nft_mint account passed: {:?}",
-            ctx.accounts.nft_mint.key()
-        );
+        if clock.unix_timestamp < session_account.start_time
+            || clock.unix_timestamp > session_account.end_time
+        {
+            return Err(ErrorCode::SessionNotActive.into());
+        }
 
-        let registration_account = ctx.accounts.registration_account.key();
+        ctx.accounts
+            .registration_account
+            .check_code_lockout(clock.unix_timestamp)?;
 
-        // let event_account = ctx.accounts.event_account.key();
-        
-        let pda_seeds = &[
-            b"nft_mint".as_ref(),
-            registration_account.as_ref(),
-        ];
+        if attentance_code != session_account.attentance_code {
+            return record_failed_code_attempt(
+                &mut ctx.accounts.event_account,
+                &mut ctx.accounts.registration_account,
+                clock.unix_timestamp,
+            );
+        }
 
-        let (calculated_pda, bump) = Pubkey::find_program_address(pda_seeds, ctx.program_id);
-        msg!("MintNft: PDA calculated on-chain: {:?}", calculated_pda);
-        msg!("MintNft: PDA bump: {:?}", bump);
+        let registration_account = &mut ctx.accounts.registration_account;
+        let session_bit = 1u64 << session_id;
 
-        if calculated_pda != ctx.accounts.nft_mint.key() {
-            msg!("Error: PDA mismatch for nft_mint");
-            return Err(anchor_lang::error::ErrorCode::ConstraintSeeds.into());
+        if registration_account.sessions_attended & session_bit != 0 {
+            return Err(ErrorCode::SessionAlreadyAttended.into());
         }
-        if attentance_code != ctx.accounts.event_account.attentance_code {
-            return Err(ErrorCode::InvalidAttentanceCode.into());
+
+        registration_account.sessions_attended |= session_bit;
+        registration_account.failed_code_attempts = 0;
+        mark_checked_in(&mut ctx.accounts.event_account, registration_account)?;
+
+        emit!(AttendeeCheckedIn {
+            event: registration_account.event,
+            attentee: registration_account.attentee,
+            registration: registration_account.key(),
+            session_id: Some(session_id),
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Checks in with the event's attendance code. A wrong code is recorded
+    /// instead of failing the transaction, so the attempt counts towards the
+    /// registration's lockout.
+    pub fn check_in(ctx: Context<CheckIn>, attentance_code: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+
+        if ctx.accounts.event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
         }
 
-        if ctx.accounts.registration_account.attentence_nft_minted {
-            return Err(ErrorCode::NftAlreadyMinted.into());
+        if ctx.accounts.event_account.session_count > 0 {
+            return Err(ErrorCode::InvalidSessionId.into());
+        }
+
+        if !ctx.accounts.event_account.geofence.is_empty() {
+            return Err(ErrorCode::AttestationRequired.into());
+        }
+
+        if ctx.accounts.registration_account.attented {
+            return Err(ErrorCode::AlreadyCheckedIn.into());
         }
 
-        if clock.unix_timestamp < ctx.accounts.event_account.start_time as i64
-            || clock.unix_timestamp > ctx.accounts.event_account.end_time as i64
+        ctx.accounts
+            .registration_account
+            .check_code_lockout(clock.unix_timestamp)?;
+
+        if !ctx
+            .accounts
+            .event_account
+            .verify_attentance_code(attentance_code, clock.unix_timestamp)?
         {
-            return Err(ErrorCode::NotMinitingTime.into());
+            return record_failed_code_attempt(
+                &mut ctx.accounts.event_account,
+                &mut ctx.accounts.registration_account,
+                clock.unix_timestamp,
+            );
         }
 
-        ctx.accounts.registration_account.attentence_nft_minted = true;
+        let registration_account = &mut ctx.accounts.registration_account;
+        registration_account.failed_code_attempts = 0;
+        mark_checked_in(&mut ctx.accounts.event_account, registration_account)?;
 
-        let nft_name = ctx.accounts.event_account.name.to_owned()
-            + ctx
-                .accounts
-                .event_account
-                .registered_attentees
-                .to_string()
-                .as_str();
-        let nft_uri = ctx.accounts.event_account.url.to_owned();
+        emit!(AttendeeCheckedIn {
+            event: registration_account.event,
+            attentee: registration_account.attentee,
+            registration: registration_account.key(),
+            session_id: None,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"collection_mint".as_ref(),
-            ctx.accounts.event_account.name.as_bytes(),
-            &[ctx.bumps.collection_mint],
-        ]];
+    pub fn set_event_deposit(
+        ctx: Context<SetEventDeposit>,
+        _name: String,
+        deposit_lamports: u64,
+        deposit_beneficiary: Pubkey,
+        deposit_claim_deadline: i64,
+    ) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
 
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.nft_mint.to_account_info(),
-                    to: ctx.accounts.destination.to_account_info(),
-                    authority: ctx.accounts.collection_mint.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            1,
-        )?;
+        if event_account.registered_attentees > 0 {
+            return Err(ErrorCode::DepositLocked.into());
+        }
 
-        msg!("creating metadata account");
+        if deposit_claim_deadline <= event_account.end_time {
+            return Err(ErrorCode::InvalidDepositDeadline.into());
+        }
 
-        create_metadata_accounts_v3(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                CreateMetadataAccountsV3 {
-                    metadata: ctx.accounts.child_nft_metadata.to_account_info(),
-                    mint: ctx.accounts.nft_mint.to_account_info(),
-                    mint_authority: ctx.accounts.collection_mint.to_account_info(),
-                    payer: ctx.accounts.attentee.to_account_info(),
-                    update_authority: ctx.accounts.collection_mint.to_account_info(),
-                    system_program: ctx.accounts.system_program.to_account_info(),
-                    rent: ctx.accounts.rent.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            DataV2 {
-                name: nft_name,
-                symbol: SYMBOL.to_string(),
-                uri: nft_uri,
-                seller_fee_basis_points: 0,
-                creators: None,
-                collection: None,
-                uses: None,
-            },
-            true,
-            true,
-            None,
-        )?;
+        // the vault is a plain system account, so it must stay rent exempt
+        // for as long as it holds any deposit
+        if deposit_lamports > 0 && deposit_lamports < Rent::get()?.minimum_balance(0) {
+            return Err(ErrorCode::DepositTooSmall.into());
+        }
 
-        msg!("creating master edition account");
+        event_account.deposit_lamports = deposit_lamports;
+        event_account.deposit_beneficiary = deposit_beneficiary;
+        event_account.deposit_claim_deadline = deposit_claim_deadline;
+        Ok(())
+    }
 
-        create_master_edition_v3(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                CreateMasterEditionV3 {
-                    edition: ctx.accounts.child_nft_master_edition.to_account_info(),
-                    mint: ctx.accounts.nft_mint.to_account_info(),
-                    update_authority: ctx.accounts.collection_mint.to_account_info(),
-                    mint_authority: ctx.accounts.collection_mint.to_account_info(),
-                    payer: ctx.accounts.attentee.to_account_info(),
-                    metadata: ctx.accounts.child_nft_metadata.to_account_info(),
-                    token_program: ctx.accounts.token_program.to_account_info(),
-                    system_program: ctx.accounts.system_program.to_account_info(),
-                    rent: ctx.accounts.rent.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            Some(0),
+    /// Switches the event to rotating attendance codes. `commitment` is the
+    /// end of a sha256 hash chain, the code of window `n` (counted from
+    /// `start_time`, starting at 1) is the element that hashes to it in `n`
+    /// steps. A zero period goes back to `commitment` as static code.
+    pub fn set_code_rotation(
+        ctx: Context<SetCodeRotation>,
+        _name: String,
+        commitment: [u8; 32],
+        code_rotation_period: u32,
+    ) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
+
+        if Clock::get()?.unix_timestamp >= event_account.start_time
+            || (code_rotation_period != 0 && code_rotation_period < MIN_CODE_ROTATION_PERIOD)
+        {
+            return Err(ErrorCode::InvalidCodeRotation.into());
+        }
+
+        event_account.attentance_code = commitment;
+        event_account.code_rotation_period = code_rotation_period;
+        event_account.code_checkpoint_window = 0;
+        event_account.check_code_windows()
+    }
+
+    /// Restricts check-in to a venue geohash. An empty geofence removes it.
+    pub fn set_geofence(
+        ctx: Context<SetGeofence>,
+        _name: String,
+        geofence: String,
+        attestation_authority: Pubkey,
+    ) -> Result<()> {
+        if geofence.len() > ATTESTED_GEOHASH_LENGTH
+            || !geofence.bytes().all(|c| GEOHASH_ALPHABET.contains(&c))
+            || (!geofence.is_empty() && attestation_authority == Pubkey::default())
+        {
+            return Err(ErrorCode::InvalidGeofence.into());
+        }
+
+        let event_account = &mut ctx.accounts.event_account;
+        event_account.geofence = geofence;
+        event_account.attestation_authority = attestation_authority;
+        Ok(())
+    }
+
+    /// Checks in with a location attestation signed by the event's
+    /// attestation authority, verified by an Ed25519 program instruction
+    /// placed right before this one.
+    pub fn check_in_with_attestation(ctx: Context<CheckInWithAttestation>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event_account = &ctx.accounts.event_account;
+
+        if event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        if event_account.geofence.is_empty() {
+            return Err(ErrorCode::NoGeofence.into());
+        }
+
+        if clock.unix_timestamp < event_account.start_time
+            || clock.unix_timestamp > event_account.end_time
+        {
+            return Err(ErrorCode::CheckInNotOpen.into());
+        }
+
+        let attestation = LocationAttestation::load(
+            &ctx.accounts.instructions,
+            &event_account.attestation_authority,
+        )?;
+
+        if attestation.attentee != ctx.accounts.attentee.key()
+            || attestation.event != event_account.key()
+        {
+            return Err(ErrorCode::InvalidAttestation.into());
+        }
+
+        // a geohash prefix covers every cell inside it
+        if !attestation
+            .geohash
+            .starts_with(event_account.geofence.as_bytes())
+        {
+            return Err(ErrorCode::OutsideGeofence.into());
+        }
+
+        if attestation.timestamp > clock.unix_timestamp
+            || clock.unix_timestamp - attestation.timestamp > ATTESTATION_MAX_AGE
+        {
+            return Err(ErrorCode::AttestationExpired.into());
+        }
+
+        let registration_account = &mut ctx.accounts.registration_account;
+
+        if registration_account.attented {
+            return Err(ErrorCode::AlreadyCheckedIn.into());
+        }
+
+        mark_checked_in(&mut ctx.accounts.event_account, registration_account)?;
+
+        emit!(AttendeeCheckedIn {
+            event: registration_account.event,
+            attentee: registration_account.attentee,
+            registration: registration_account.key(),
+            session_id: None,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn transfer_collection_authority(
+        ctx: Context<TransferCollectionAuthority>,
+        _name: String,
+    ) -> Result<()> {
+        let collection_mint_seeds = ctx.accounts.event_account.collection_mint_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&collection_mint_seeds];
+
+        hand_over_collection_authority(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.collection_mint.to_account_info(),
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.collection_authority_record.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            signer_seeds,
+        )
+    }
+
+    pub fn transfer_series_collection_authority(
+        ctx: Context<TransferSeriesCollectionAuthority>,
+        _name: String,
+    ) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"series_collection_mint".as_ref(),
+            ctx.accounts.series_account.creator.as_ref(),
+            ctx.accounts.series_account.name.as_bytes(),
+            &[ctx.accounts.series_account.collection_mint_bump],
+        ]];
+
+        hand_over_collection_authority(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.collection_mint.to_account_info(),
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.collection_authority_record.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            signer_seeds,
+        )
+    }
+
+    pub fn close_event(ctx: Context<CloseEvent>) -> Result<()> {
+        debug_msg!("closing account: {}", ctx.accounts.event_account.key());
+
+        // closing an event before it ended counts as cancelling it
+        let event_account = &ctx.accounts.event_account;
+
+        // registrations still point at the event, they are cancelled or
+        // cranked closed first
+        if event_account.open_registrations > 0 {
+            return Err(ErrorCode::EventNotEmpty.into());
+        }
+
+        if ctx.accounts.vault.lamports() > 0 {
+            return Err(ErrorCode::VaultNotEmpty.into());
+        }
+
+        if !event_account.cancelled {
+            let cancelled = Clock::get()?.unix_timestamp < event_account.end_time;
+            ctx.accounts
+                .organizer_account
+                .record_outcome(event_account, cancelled)?;
+        }
+        Ok(())
+    }
+
+    /// Cancels an event that has not ended yet. Registration and check-in
+    /// stop, and attentees can cancel their registration for a full deposit
+    /// refund. Ticket prices were paid to the organizer and are refunded off
+    /// chain.
+    pub fn cancel_event(ctx: Context<CancelEvent>, _name: String) -> Result<()> {
+        let clock = Clock::get()?;
+        let event_account = &mut ctx.accounts.event_account;
+
+        if event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        if clock.unix_timestamp >= event_account.end_time {
+            return Err(ErrorCode::EventAlreadyEnded.into());
+        }
+
+        event_account.cancelled = true;
+        ctx.accounts
+            .organizer_account
+            .record_outcome(event_account, true)?;
+
+        emit!(EventCancelled {
+            event: event_account.key(),
+            creator: event_account.creator,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Puts the attentee on the waitlist of a full event. Spots that open up
+    /// still go to whoever registers first, the waitlist tells the
+    /// organizer how much more capacity is wanted.
+    pub fn join_waitlist(ctx: Context<JoinWaitlist>) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
+
+        if event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        if event_account.registered_attentees < event_account.total_attentees {
+            return Err(ErrorCode::EventNotFull.into());
+        }
+
+        if !ctx.accounts.registration_account.data_is_empty() {
+            return Err(ErrorCode::AlreadyRegistered.into());
+        }
+
+        event_account.waitlisted_attentees = event_account
+            .waitlisted_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        *ctx.accounts.waitlist_entry = WaitlistEntry {
+            event: event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            payer: ctx.accounts.payer.key(),
+            joined_at: Clock::get()?.unix_timestamp,
+            bump: ctx.bumps.waitlist_entry,
+        };
+        Ok(())
+    }
+
+    pub fn leave_waitlist(ctx: Context<LeaveWaitlist>) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
+        event_account.waitlisted_attentees = event_account
+            .waitlisted_attentees
+            .checked_sub(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(())
+    }
+
+    /// Returns the event's counters, for clients to read through simulation.
+    pub fn event_stats(ctx: Context<GetEventStats>) -> Result<EventStats> {
+        Ok(ctx.accounts.event_account.stats())
+    }
+
+    /// Issues `code_count` single-use claim codes. `merkle_root` commits to
+    /// the leaves `sha256("claim_code" || index as u32 le || code)`, hashed
+    /// pairwise in sorted order.
+    pub fn set_claim_codes(
+        ctx: Context<SetClaimCodes>,
+        _name: String,
+        merkle_root: [u8; 32],
+        code_count: u32,
+    ) -> Result<()> {
+        if code_count == 0 || code_count > MAX_CLAIM_CODES {
+            return Err(ErrorCode::InvalidClaimCodes.into());
+        }
+
+        *ctx.accounts.claim_codes_account = ClaimCodes {
+            event: ctx.accounts.event_account.key(),
+            merkle_root,
+            code_count,
+            spent: vec![0; code_count.div_ceil(8) as usize],
+            bump: ctx.bumps.claim_codes_account,
+        };
+        ctx.accounts.event_account.claim_code_count = code_count;
+        Ok(())
+    }
+
+    /// Lets wallets that never registered claim a badge at the door with the
+    /// attendance code, taking a seat in `tier_id`. `None` turns it off.
+    pub fn set_walk_in_tier(
+        ctx: Context<SetWalkInTier>,
+        _name: String,
+        tier_id: Option<u8>,
+    ) -> Result<()> {
+        ctx.accounts.event_account.walk_in_tier = tier_id;
+        Ok(())
+    }
+
+    /// Pauses registrations, mints and deposit payouts of one event, e.g.
+    /// while the organizer sorts out a leaked attendance code.
+    pub fn set_event_paused(
+        ctx: Context<SetEventPaused>,
+        _name: String,
+        paused: bool,
+    ) -> Result<()> {
+        ctx.accounts.event_account.paused = paused;
+        Ok(())
+    }
+
+    /// Sets the questions attentees answer when registering. Questions are
+    /// fixed once someone has registered, so answers stay comparable.
+    pub fn set_registration_questions(
+        ctx: Context<SetRegistrationQuestions>,
+        _name: String,
+        questions: Vec<RegistrationQuestion>,
+    ) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
+
+        if event_account.registered_attentees > 0 {
+            return Err(ErrorCode::QuestionsLocked.into());
+        }
+
+        if questions.len() > MAX_REGISTRATION_QUESTIONS
+            || questions.iter().any(|question| {
+                question.prompt.len() > MAX_QUESTION_LENGTH
+                    || (question.kind == QuestionKind::Choice && question.choices < 2)
+            })
+        {
+            return Err(ErrorCode::InvalidQuestions.into());
+        }
+
+        event_account.question_count = questions.len() as u8;
+
+        *ctx.accounts.questions_account = RegistrationQuestions {
+            event: event_account.key(),
+            questions,
+            bump: ctx.bumps.questions_account,
+        };
+        Ok(())
+    }
+
+    /// Creates or updates the attentee's profile, shared by all events.
+    pub fn update_profile(
+        ctx: Context<UpdateProfile>,
+        display_name: String,
+        contact_hash: [u8; 32],
+        social_handle: String,
+        preferences: u32,
+    ) -> Result<()> {
+        if display_name.len() > MAX_PROFILE_FIELD_LENGTH
+            || social_handle.len() > MAX_PROFILE_FIELD_LENGTH
+        {
+            return Err(ErrorCode::InvalidProfile.into());
+        }
+
+        *ctx.accounts.profile_account = AttendeeProfile {
+            attentee: ctx.accounts.attentee.key(),
+            display_name,
+            contact_hash,
+            social_handle,
+            preferences,
+            bump: ctx.bumps.profile_account,
+        };
+        Ok(())
+    }
+
+    pub fn close_profile(_ctx: Context<CloseProfile>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn register_event(
+        ctx: Context<RegisterEvent>,
+        tier_id: u8,
+        answers: Vec<RegistrationAnswer>,
+    ) -> Result<()> {
+        // let clock = Clock::get()?;
+
+        // if clock.slot > ctx.accounts.event_account.start_time as u64
+        //     && clock.slot < ctx.accounts.event_account.end_time as u64
+        // {
+        //     return Err(ErrorCode::RegistrationNotOpenYet.into());
+        // }
+
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        if ctx.accounts.event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        // capacity can be edited below the current count, so compare with >=
+        if ctx.accounts.event_account.registered_attentees
+            >= ctx.accounts.event_account.total_attentees
+        {
+            return Err(ErrorCode::RegistrationCompleted.into());
+        }
+
+        let clock = Clock::get()?;
+        let tier_account = &ctx.accounts.tier_account;
+
+        if clock.unix_timestamp < tier_account.registration_start
+            || clock.unix_timestamp > tier_account.registration_end
+        {
+            return Err(ErrorCode::TierRegistrationClosed.into());
+        }
+
+        if tier_account.registered_attentees >= tier_account.capacity {
+            return Err(ErrorCode::TierSoldOut.into());
+        }
+
+        check_answers(
+            &ctx.accounts.event_account,
+            ctx.accounts.questions_account.as_deref(),
+            &answers,
         )?;
 
-        msg!("Verifying collection");
-        set_and_verify_sized_collection_item(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                SetAndVerifySizedCollectionItem {
-                    metadata: ctx.accounts.child_nft_metadata.to_account_info(),
-                    collection_authority: ctx.accounts.collection_mint.to_account_info(),
-                    payer: ctx.accounts.attentee.to_account_info(),
-                    update_authority: ctx.accounts.collection_mint.to_account_info(),
-                    collection_mint: ctx.accounts.collection_mint.to_account_info(),
-                    collection_metadata: ctx.accounts.metadata.to_account_info(),
-                    collection_master_edition: ctx.accounts.master_edition.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            None,
-        )?;
+        let platform_fee = ctx.accounts.config.platform_fee(tier_account.price);
+
+        if platform_fee > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.attentee.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                ),
+                platform_fee,
+            )?;
+        }
+
+        if tier_account.price > platform_fee {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.attentee.to_account_info(),
+                        to: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                tier_account.price - platform_fee,
+            )?;
+        }
+
+        let deposit_lamports = ctx.accounts.event_account.deposit_lamports;
+
+        if deposit_lamports > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.attentee.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                deposit_lamports,
+            )?;
+        }
+
+        *ctx.accounts.registration_account = EventRegistration {
+            event: ctx.accounts.event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            payer: ctx.accounts.payer.key(),
+            tier: tier_id,
+            registered: true,
+            attented: false,
+            attentence_nft_minted: false,
+            sessions_attended: 0,
+            series_attendance_recorded: false,
+            deposit_paid: deposit_lamports,
+            failed_code_attempts: 0,
+            code_locked_until: 0,
+            answers,
+            bump: ctx.bumps.registration_account,
+        };
+
+        let tier_account = &mut ctx.accounts.tier_account;
+        tier_account.registered_attentees = tier_account
+            .registered_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        let event_account: &mut Account<'_, Event> = &mut ctx.accounts.event_account;
+        event_account.registered_attentees = event_account
+            .registered_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        event_account.open_registrations = event_account
+            .open_registrations
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        let waitlist_entry = ctx.accounts.waitlist_entry.to_account_info();
+
+        if waitlist_entry.owner == &crate::ID && !waitlist_entry.data_is_empty() {
+            let entry = WaitlistEntry::try_deserialize(&mut &waitlist_entry.data.borrow()[..])?;
+
+            // the rent goes back to whoever paid for the entry
+            let rent_destination = if entry.payer == ctx.accounts.payer.key() {
+                ctx.accounts.payer.to_account_info()
+            } else if entry.payer == ctx.accounts.attentee.key() {
+                ctx.accounts.attentee.to_account_info()
+            } else {
+                match &ctx.accounts.waitlist_payer {
+                    Some(waitlist_payer) if waitlist_payer.key() == entry.payer => {
+                        waitlist_payer.to_account_info()
+                    }
+                    _ => return Err(ErrorCode::WaitlistPayerMismatch.into()),
+                }
+            };
+
+            close_program_account(&waitlist_entry, &rent_destination)?;
+            event_account.waitlisted_attentees = event_account
+                .waitlisted_attentees
+                .checked_sub(1)
+                .ok_or(ErrorCode::OverflowError)?;
+        }
+
+        emit!(AttendeeRegistered {
+            event: event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            registration: ctx.accounts.registration_account.key(),
+            tier: tier_id,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_registration(ctx: Context<CancelRegistration>) -> Result<()> {
+        let event_account = &mut ctx.accounts.event_account;
+        event_account.registered_attentees = event_account
+            .registered_attentees
+            .checked_sub(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        event_account.open_registrations = event_account
+            .open_registrations
+            .checked_sub(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        event_account.cancelled_registrations = event_account
+            .cancelled_registrations
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        if ctx.accounts.registration_account.attented {
+            event_account.checked_in_attentees = event_account
+                .checked_in_attentees
+                .checked_sub(1)
+                .ok_or(ErrorCode::OverflowError)?;
+        }
+
+        let tier_account = &mut ctx.accounts.tier_account;
+        tier_account.registered_attentees = tier_account
+            .registered_attentees
+            .checked_sub(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        // deposits are only refunded when cancelling before the event starts
+        // or after the organizer cancelled it, later cancellations are
+        // treated as no-shows
+        let clock = Clock::get()?;
+        let deposit_paid = ctx.accounts.registration_account.deposit_paid;
+
+        if deposit_paid > 0
+            && (clock.unix_timestamp < ctx.accounts.event_account.start_time
+                || ctx.accounts.event_account.cancelled)
+        {
+            let event_key = ctx.accounts.event_account.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"vault".as_ref(),
+                event_key.as_ref(),
+                &[ctx.accounts.event_account.vault_bump],
+            ]];
+
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.attentee.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                deposit_paid,
+            )?;
+        }
+
+        emit!(RegistrationCancelled {
+            event: ctx.accounts.event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            registration: ctx.accounts.registration_account.key(),
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn reclaim_deposit(ctx: Context<ReclaimDeposit>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event_account = &ctx.accounts.event_account;
+        let registration_account = &mut ctx.accounts.registration_account;
+
+        if clock.unix_timestamp <= event_account.end_time
+            || clock.unix_timestamp > event_account.deposit_claim_deadline
+        {
+            return Err(ErrorCode::DepositClaimClosed.into());
+        }
+
+        if !registration_account.attented {
+            return Err(ErrorCode::NotAttended.into());
+        }
+
+        if registration_account.deposit_paid == 0 {
+            return Err(ErrorCode::NoDepositToClaim.into());
+        }
+
+        let deposit_paid = registration_account.deposit_paid;
+        registration_account.deposit_paid = 0;
+
+        let event_key = event_account.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vault".as_ref(),
+            event_key.as_ref(),
+            &[ctx.accounts.event_account.vault_bump],
+        ]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.attentee.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            deposit_paid,
+        )
+    }
+
+    pub fn claim_forfeited_deposits(ctx: Context<ClaimForfeitedDeposits>) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+
+        if clock.unix_timestamp <= ctx.accounts.event_account.deposit_claim_deadline {
+            return Err(ErrorCode::DepositClaimClosed.into());
+        }
+
+        // deposits of cancelled events stay refundable to the attentees
+        if ctx.accounts.event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        let forfeited = ctx.accounts.vault.lamports();
+
+        if forfeited == 0 {
+            return Err(ErrorCode::NoDepositToClaim.into());
+        }
+
+        let event_key = ctx.accounts.event_account.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vault".as_ref(),
+            event_key.as_ref(),
+            &[ctx.accounts.event_account.vault_bump],
+        ]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.beneficiary.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            forfeited,
+        )
+    }
+
+    /// Opens a pool of `mint` tokens for the attentees who check in. With a
+    /// zero `amount_per_attentee` the pool is split equally between them.
+    pub fn create_sponsor_pool(
+        ctx: Context<CreateSponsorPool>,
+        amount_per_attentee: u64,
+        refund_deadline: i64,
+    ) -> Result<()> {
+        if refund_deadline <= ctx.accounts.event_account.end_time {
+            return Err(ErrorCode::InvalidSponsorPool.into());
+        }
+
+        // claims need the registration, so the crank waits for the pool
+        let event_account = &mut ctx.accounts.event_account;
+        event_account.sponsor_refund_deadline =
+            event_account.sponsor_refund_deadline.max(refund_deadline);
+
+        *ctx.accounts.sponsor_pool = SponsorPool {
+            event: ctx.accounts.event_account.key(),
+            sponsor: ctx.accounts.sponsor.key(),
+            mint: ctx.accounts.mint.key(),
+            amount_per_attentee,
+            refund_deadline,
+            deposited: 0,
+            claimed: 0,
+            claimants: 0,
+            equal_share: 0,
+            bump: ctx.bumps.sponsor_pool,
+        };
+        Ok(())
+    }
+
+    /// Anyone can top up a pool until the event ends, leftovers go back to
+    /// the pool's sponsor.
+    pub fn fund_sponsor_pool(ctx: Context<FundSponsorPool>, amount: u64) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+        let event_account = &ctx.accounts.event_account;
+
+        if event_account.cancelled || clock.unix_timestamp > event_account.end_time {
+            return Err(ErrorCode::SponsorPoolClosed.into());
+        }
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let sponsor_pool = &mut ctx.accounts.sponsor_pool;
+        sponsor_pool.deposited = sponsor_pool
+            .deposited
+            .checked_add(amount)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(())
+    }
+
+    pub fn claim_sponsor_reward(ctx: Context<ClaimSponsorReward>) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+        let event_account = &ctx.accounts.event_account;
+
+        if event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        if clock.unix_timestamp <= event_account.end_time
+            || clock.unix_timestamp > ctx.accounts.sponsor_pool.refund_deadline
+        {
+            return Err(ErrorCode::SponsorClaimClosed.into());
+        }
+
+        if !ctx.accounts.registration_account.attented {
+            return Err(ErrorCode::NotAttended.into());
+        }
+
+        let sponsor_pool = &mut ctx.accounts.sponsor_pool;
+        let amount = sponsor_pool.reward(event_account.checked_in_attentees)?;
+
+        let mint_key = sponsor_pool.mint;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"sponsor_pool".as_ref(),
+            sponsor_pool.event.as_ref(),
+            sponsor_pool.sponsor.as_ref(),
+            mint_key.as_ref(),
+            &[sponsor_pool.bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.attentee_token_account.to_account_info(),
+                    authority: sponsor_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        *ctx.accounts.sponsor_claim = SponsorClaim {
+            pool: ctx.accounts.sponsor_pool.key(),
+            registration: ctx.accounts.registration_account.key(),
+            amount,
+            bump: ctx.bumps.sponsor_claim,
+        };
+
+        emit!(SponsorRewardClaimed {
+            pool: ctx.accounts.sponsor_pool.key(),
+            attentee: ctx.accounts.attentee.key(),
+            mint: mint_key,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Returns what is left in a pool to its sponsor once claims are over, or
+    /// right away if the event was cancelled or closed.
+    pub fn reclaim_sponsor_pool(ctx: Context<ReclaimSponsorPool>) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // nobody can claim from a closed event, so its pools are refunded
+        // without waiting for the deadline
+        let event_info = &ctx.accounts.event_account;
+        let claims_open = event_info.owner == &crate::ID
+            && !event_info.data_is_empty()
+            && !Event::try_deserialize(&mut &event_info.data.borrow()[..])?.cancelled
+            && clock.unix_timestamp <= ctx.accounts.sponsor_pool.refund_deadline;
+
+        if claims_open {
+            return Err(ErrorCode::SponsorRefundNotOpen.into());
+        }
+
+        let sponsor_pool = &ctx.accounts.sponsor_pool;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"sponsor_pool".as_ref(),
+            sponsor_pool.event.as_ref(),
+            sponsor_pool.sponsor.as_ref(),
+            sponsor_pool.mint.as_ref(),
+            &[sponsor_pool.bump],
+        ]];
+
+        let leftover = ctx.accounts.pool_token_account.amount;
+
+        if leftover > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.pool_token_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.sponsor_token_account.to_account_info(),
+                        authority: sponsor_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                leftover,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.pool_token_account.to_account_info(),
+                destination: ctx.accounts.sponsor.to_account_info(),
+                authority: sponsor_pool.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+
+    pub fn close_stale_registration(ctx: Context<CloseStaleRegistration>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event_account = &mut ctx.accounts.event_account;
+
+        if clock.unix_timestamp <= event_account.cleanup_time()? {
+            return Err(ErrorCode::CleanupNotAllowedYet.into());
+        }
+
+        event_account.open_registrations = event_account
+            .open_registrations
+            .checked_sub(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        // deposits of cancelled events are never forfeited, so they go back
+        // to the attentee rather than staying behind in the vault
+        let deposit_paid = ctx.accounts.registration_account.deposit_paid;
+
+        if deposit_paid > 0 && ctx.accounts.event_account.cancelled {
+            let event_key = ctx.accounts.event_account.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"vault".as_ref(),
+                event_key.as_ref(),
+                &[ctx.accounts.event_account.vault_bump],
+            ]];
+
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.attentee.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                deposit_paid,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn close_expired_event(ctx: Context<CloseExpiredEvent>) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+        let event_account = &ctx.accounts.event_account;
+
+        if clock.unix_timestamp <= event_account.cleanup_time()? {
+            return Err(ErrorCode::CleanupNotAllowedYet.into());
+        }
+
+        if event_account.open_registrations > 0 {
+            return Err(ErrorCode::EventNotEmpty.into());
+        }
+
+        if ctx.accounts.vault.lamports() > 0 {
+            return Err(ErrorCode::VaultNotEmpty.into());
+        }
+
+        if !event_account.cancelled {
+            ctx.accounts
+                .organizer_account
+                .record_outcome(event_account, false)?;
+        }
+
+        // the rest of the rent goes back to the creator when the account closes
+        let bounty = CLEANUP_BOUNTY_LAMPORTS.min(event_account.get_lamports());
+        event_account.sub_lamports(bounty)?;
+        ctx.accounts.cranker.add_lamports(bounty)?;
+        Ok(())
+    }
+
+    pub fn mint_nft(ctx: Context<MintNft>, claim_code: Option<ClaimCode>) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+
+        // nft_mint is already checked against its seeds by the account
+        // constraints, so there is nothing to re-derive here
+        debug_msg!(
+            "MintNft: event {} attentee {} nft_mint {}",
+            ctx.accounts.event_account.key(),
+            ctx.accounts.attentee.key(),
+            ctx.accounts.nft_mint.key()
+        );
+
+        if ctx.accounts.registration_account.attentence_nft_minted {
+            return Err(ErrorCode::NftAlreadyMinted.into());
+        }
+
+        if clock.unix_timestamp < ctx.accounts.event_account.start_time
+            || clock.unix_timestamp > ctx.accounts.event_account.end_time
+        {
+            return Err(ErrorCode::NotMinitingTime.into());
+        }
+
+        if ctx.accounts.event_account.session_count > 0 {
+            // multi-session events check in per session, so the badge is gated
+            // on the number of sessions attended instead of the event code
+            let sessions_attended = ctx
+                .accounts
+                .registration_account
+                .sessions_attended
+                .count_ones();
+            let sessions_required = ctx.accounts.event_account.min_sessions_for_badge.max(1) as u32;
+
+            if sessions_attended < sessions_required {
+                return Err(ErrorCode::NotEnoughSessionsAttended.into());
+            }
+        } else if ctx.accounts.event_account.claim_code_count > 0 {
+            // a code can be passed on, so it does not replace the attested
+            // check-in of a geofenced event
+            if !ctx.accounts.event_account.geofence.is_empty()
+                && !ctx.accounts.registration_account.attented
+            {
+                return Err(ErrorCode::AttestationRequired.into());
+            }
+
+            // a shared attendance code cannot tell claimers apart, so each
+            // badge spends a code of its own
+            let (Some(claim_codes_account), Some(claim_code)) =
+                (ctx.accounts.claim_codes_account.as_mut(), claim_code)
+            else {
+                return Err(ErrorCode::ClaimCodeRequired.into());
+            };
+
+            claim_codes_account.spend(&claim_code)?;
+            mark_checked_in(
+                &mut ctx.accounts.event_account,
+                &mut ctx.accounts.registration_account,
+            )?;
+        } else if !ctx.accounts.registration_account.attented {
+            // the code is checked by `check_in`, where wrong guesses count
+            // towards a lockout, or replaced by an attested check-in on
+            // geofenced events
+            return Err(ErrorCode::NotAttended.into());
+        }
+
+        ctx.accounts.registration_account.attentence_nft_minted = true;
+
+        let collection_mint_seeds = ctx.accounts.event_account.collection_mint_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&collection_mint_seeds];
+
+        BadgeNft {
+            payer: ctx.accounts.payer.to_account_info(),
+            creator: None,
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            destination: ctx.accounts.destination.to_account_info(),
+            metadata: ctx.accounts.child_nft_metadata.to_account_info(),
+            master_edition: ctx.accounts.child_nft_master_edition.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: ctx.accounts.metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.master_edition.to_account_info(),
+            collection_authority_record: ctx.accounts.collection_authority_record.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .mint(
+            badge_data(
+                &ctx.accounts.event_account,
+                ctx.accounts.tier_account.uri.clone(),
+            ),
+            signer_seeds,
+        )?;
+
+        mark_minted(
+            &mut ctx.accounts.event_account,
+            &mut ctx.accounts.registration_account,
+        )?;
+        ctx.accounts.registration_account.attented = true;
+
+        emit!(BadgeMinted {
+            event: ctx.accounts.event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            registration: ctx.accounts.registration_account.key(),
+            mint: ctx.accounts.nft_mint.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Registers, checks in and mints a badge for a walk-in in one go. Walk-ins
+    /// skip the registration questions and the deposit, they already showed up.
+    pub fn walk_in_mint(
+        ctx: Context<WalkInMint>,
+        tier_id: u8,
+        attentance_code: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        let clock = Clock::get()?;
+        let event_account = &mut ctx.accounts.event_account;
+
+        if event_account.cancelled {
+            return Err(ErrorCode::EventCancelled.into());
+        }
+
+        if event_account.walk_in_tier != Some(tier_id) || ctx.accounts.tier_account.price > 0 {
+            return Err(ErrorCode::WalkInNotAllowed.into());
+        }
+
+        if event_account.session_count > 0 {
+            return Err(ErrorCode::InvalidSessionId.into());
+        }
+
+        if !event_account.geofence.is_empty() {
+            return Err(ErrorCode::AttestationRequired.into());
+        }
+
+        if event_account.claim_code_count > 0 {
+            return Err(ErrorCode::ClaimCodeRequired.into());
+        }
+
+        if event_account.registered_attentees >= event_account.total_attentees {
+            return Err(ErrorCode::RegistrationCompleted.into());
+        }
+
+        if ctx.accounts.tier_account.registered_attentees >= ctx.accounts.tier_account.capacity {
+            return Err(ErrorCode::TierSoldOut.into());
+        }
+
+        // a failed walk-in leaves no registration behind to count the wrong
+        // guess against, so the whole instruction fails instead
+        if !event_account.verify_attentance_code(attentance_code, clock.unix_timestamp)? {
+            return Err(ErrorCode::InvalidAttentanceCode.into());
+        }
+
+        *ctx.accounts.registration_account = EventRegistration {
+            event: event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            payer: ctx.accounts.payer.key(),
+            tier: tier_id,
+            registered: true,
+            attented: false,
+            attentence_nft_minted: true,
+            sessions_attended: 0,
+            series_attendance_recorded: false,
+            deposit_paid: 0,
+            failed_code_attempts: 0,
+            code_locked_until: 0,
+            answers: Vec::new(),
+            bump: ctx.bumps.registration_account,
+        };
+
+        event_account.registered_attentees = event_account
+            .registered_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        event_account.open_registrations = event_account
+            .open_registrations
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        event_account.minted_attentees = event_account
+            .minted_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        mark_checked_in(event_account, &mut ctx.accounts.registration_account)?;
+
+        let tier_account = &mut ctx.accounts.tier_account;
+        tier_account.registered_attentees = tier_account
+            .registered_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        let collection_mint_seeds = ctx.accounts.event_account.collection_mint_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&collection_mint_seeds];
+
+        BadgeNft {
+            payer: ctx.accounts.payer.to_account_info(),
+            creator: None,
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            destination: ctx.accounts.destination.to_account_info(),
+            metadata: ctx.accounts.child_nft_metadata.to_account_info(),
+            master_edition: ctx.accounts.child_nft_master_edition.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: ctx.accounts.metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.master_edition.to_account_info(),
+            collection_authority_record: ctx.accounts.collection_authority_record.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .mint(
+            badge_data(
+                &ctx.accounts.event_account,
+                ctx.accounts.tier_account.uri.clone(),
+            ),
+            signer_seeds,
+        )?;
+
+        let event_key = ctx.accounts.event_account.key();
+        let attentee_key = ctx.accounts.attentee.key();
+        let registration_key = ctx.accounts.registration_account.key();
+
+        emit!(AttendeeRegistered {
+            event: event_key,
+            attentee: attentee_key,
+            registration: registration_key,
+            tier: tier_id,
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(AttendeeCheckedIn {
+            event: event_key,
+            attentee: attentee_key,
+            registration: registration_key,
+            session_id: None,
+            timestamp: clock.unix_timestamp,
+        });
+        emit!(BadgeMinted {
+            event: event_key,
+            attentee: attentee_key,
+            registration: registration_key,
+            mint: ctx.accounts.nft_mint.key(),
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn airdrop_badge(ctx: Context<AirdropBadge>) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        if ctx.accounts.registration_account.attentence_nft_minted {
+            return Err(ErrorCode::NftAlreadyMinted.into());
+        }
+
+        let collection_mint_seeds = ctx.accounts.event_account.collection_mint_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&collection_mint_seeds];
+
+        BadgeNft {
+            payer: ctx.accounts.creator.to_account_info(),
+            creator: Some(ctx.accounts.creator.to_account_info()),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            destination: ctx.accounts.destination.to_account_info(),
+            metadata: ctx.accounts.child_nft_metadata.to_account_info(),
+            master_edition: ctx.accounts.child_nft_master_edition.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: ctx.accounts.metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.master_edition.to_account_info(),
+            collection_authority_record: ctx.accounts.collection_authority_record.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        }
+        .mint(
+            badge_data(
+                &ctx.accounts.event_account,
+                ctx.accounts.tier_account.uri.clone(),
+            ),
+            signer_seeds,
+        )?;
+
+        mark_minted(
+            &mut ctx.accounts.event_account,
+            &mut ctx.accounts.registration_account,
+        )?;
+
+        emit!(BadgeMinted {
+            event: ctx.accounts.event_account.key(),
+            attentee: ctx.accounts.attentee.key(),
+            registration: ctx.accounts.registration_account.key(),
+            mint: ctx.accounts.nft_mint.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Airdrops badges to several registrations of the same tier. Each badge
+    /// takes `AIRDROP_ACCOUNTS_PER_BADGE` remaining accounts, in order:
+    /// attentee, registration_account, nft_mint, child_nft_metadata,
+    /// child_nft_master_edition and destination.
+    pub fn airdrop_badges<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AirdropBadges<'info>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .config
+            .check_not_paused(&ctx.accounts.event_account)?;
+
+        // `usize::is_multiple_of` is newer than the platform tools' rustc
+        #[allow(clippy::manual_is_multiple_of)]
+        let uneven = ctx.remaining_accounts.len() % AIRDROP_ACCOUNTS_PER_BADGE != 0;
+
+        if ctx.remaining_accounts.is_empty() || uneven {
+            return Err(ErrorCode::InvalidAirdropAccounts.into());
+        }
+
+        let event_key = ctx.accounts.event_account.key();
+        let collection_mint_key = ctx.accounts.collection_mint.key();
+        let mint_rent = Rent::get()?.minimum_balance(SplMint::LEN);
+        let clock = Clock::get()?;
+
+        let collection_mint_seeds = ctx.accounts.event_account.collection_mint_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&collection_mint_seeds];
+
+        let mut minted = 0u64;
+
+        for badge_accounts in ctx.remaining_accounts.chunks(AIRDROP_ACCOUNTS_PER_BADGE) {
+            let [attentee, registration_info, nft_mint, child_nft_metadata, child_nft_master_edition, destination] =
+                badge_accounts
+            else {
+                return Err(ErrorCode::InvalidAirdropAccounts.into());
+            };
+
+            let mut registration_account =
+                Account::<EventRegistration>::try_from(registration_info)?;
+
+            if registration_account.event != event_key
+                || registration_account.attentee != attentee.key()
+                || registration_account.tier != ctx.accounts.tier_account.tier_id
+            {
+                return Err(ErrorCode::InvalidAirdropAccounts.into());
+            }
+
+            if registration_account.attentence_nft_minted {
+                return Err(ErrorCode::NftAlreadyMinted.into());
+            }
+
+            // remaining accounts skip the `init` constraints, so the badge
+            // mint PDA and the attentee's token account are created here
+            let registration_key = registration_info.key();
+            let (nft_mint_key, nft_mint_bump) = Pubkey::find_program_address(
+                &[b"nft_mint".as_ref(), registration_key.as_ref()],
+                ctx.program_id,
+            );
+
+            if nft_mint.key() != nft_mint_key {
+                return Err(anchor_lang::error::ErrorCode::ConstraintSeeds.into());
+            }
+
+            create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    CreateAccount {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: nft_mint.clone(),
+                    },
+                    &[&[
+                        b"nft_mint".as_ref(),
+                        registration_key.as_ref(),
+                        &[nft_mint_bump],
+                    ]],
+                ),
+                mint_rent,
+                SplMint::LEN as u64,
+                ctx.accounts.token_program.key,
+            )?;
+
+            initialize_mint2(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    InitializeMint2 {
+                        mint: nft_mint.clone(),
+                    },
+                ),
+                0,
+                &collection_mint_key,
+                Some(&collection_mint_key),
+            )?;
+
+            create_associated_token_account(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                CreateAssociatedTokenAccount {
+                    payer: ctx.accounts.creator.to_account_info(),
+                    associated_token: destination.clone(),
+                    authority: attentee.clone(),
+                    mint: nft_mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ))?;
+
+            BadgeNft {
+                payer: ctx.accounts.creator.to_account_info(),
+                creator: Some(ctx.accounts.creator.to_account_info()),
+                mint: nft_mint.clone(),
+                destination: destination.clone(),
+                metadata: child_nft_metadata.clone(),
+                master_edition: child_nft_master_edition.clone(),
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.master_edition.to_account_info(),
+                collection_authority_record: ctx
+                    .accounts
+                    .collection_authority_record
+                    .to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            }
+            .mint(
+                badge_data(
+                    &ctx.accounts.event_account,
+                    ctx.accounts.tier_account.uri.clone(),
+                ),
+                signer_seeds,
+            )?;
+
+            registration_account.attentence_nft_minted = true;
+            registration_account.exit(ctx.program_id)?;
+            minted = minted.checked_add(1).ok_or(ErrorCode::OverflowError)?;
+
+            emit!(BadgeMinted {
+                event: event_key,
+                attentee: attentee.key(),
+                registration: registration_key,
+                mint: nft_mint_key,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        let event_account = &mut ctx.accounts.event_account;
+        event_account.minted_attentees = event_account
+            .minted_attentees
+            .checked_add(minted)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(())
+    }
+}
+
+/// Accounts needed to mint a sized Metaplex collection NFT owned by a
+/// `collection_mint` PDA.
+struct CollectionNft<'info> {
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    master_edition: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+}
+
+impl<'info> CollectionNft<'info> {
+    fn create(&self, data: DataV2, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        debug_msg!("creating mint account...");
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    mint: self.mint.clone(),
+                    to: self.token_account.clone(),
+                    authority: self.mint.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        debug_msg!("creating metadata account");
+
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.metadata.clone(),
+                    mint: self.mint.clone(),
+                    mint_authority: self.mint.clone(),
+                    payer: self.payer.clone(),
+                    update_authority: self.mint.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            data,
+            true,
+            true,
+            Some(CollectionDetails::V1 { size: 0 }),
+        )?;
+
+        debug_msg!("creating master edition account");
+
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMasterEditionV3 {
+                    edition: self.master_edition.clone(),
+                    mint: self.mint.clone(),
+                    update_authority: self.mint.clone(),
+                    mint_authority: self.mint.clone(),
+                    payer: self.payer.clone(),
+                    metadata: self.metadata.clone(),
+                    token_program: self.token_program.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            Some(0),
+        )?;
+
+        debug_msg!("verifying the organizer as creator...");
+
+        sign_metadata(CpiContext::new(
+            self.token_metadata_program.clone(),
+            SignMetadata {
+                creator: self.payer.clone(),
+                metadata: self.metadata.clone(),
+            },
+        ))?;
+
+        Ok(())
+    }
+}
+
+/// Accounts needed to mint a badge NFT and verify it into the event's sized
+/// collection, signed by the `collection_mint` PDA. Airdrops pass the
+/// organizer as `creator` to verify it as the badge's creator, self-served
+/// badges are vouched for by the verified collection alone.
+struct BadgeNft<'info> {
+    payer: AccountInfo<'info>,
+    creator: Option<AccountInfo<'info>>,
+    mint: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    master_edition: AccountInfo<'info>,
+    collection_mint: AccountInfo<'info>,
+    collection_metadata: AccountInfo<'info>,
+    collection_master_edition: AccountInfo<'info>,
+    collection_authority_record: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+}
+
+impl<'info> BadgeNft<'info> {
+    fn mint(&self, data: DataV2, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    mint: self.mint.clone(),
+                    to: self.destination.clone(),
+                    authority: self.collection_mint.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        debug_msg!("creating metadata account");
+
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.metadata.clone(),
+                    mint: self.mint.clone(),
+                    mint_authority: self.collection_mint.clone(),
+                    payer: self.payer.clone(),
+                    update_authority: self.collection_mint.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            data,
+            true,
+            true,
+            None,
+        )?;
+
+        debug_msg!("creating master edition account");
+
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                CreateMasterEditionV3 {
+                    edition: self.master_edition.clone(),
+                    mint: self.mint.clone(),
+                    update_authority: self.collection_mint.clone(),
+                    mint_authority: self.collection_mint.clone(),
+                    payer: self.payer.clone(),
+                    metadata: self.metadata.clone(),
+                    token_program: self.token_program.clone(),
+                    system_program: self.system_program.clone(),
+                    rent: self.rent.clone(),
+                },
+                signer_seeds,
+            ),
+            Some(0),
+        )?;
+
+        debug_msg!("Verifying collection");
+
+        // once the creator holds the collection update authority the
+        // collection mint PDA verifies through its delegate record instead
+        let (record, remaining_accounts) = if self.collection_authority_record.data_is_empty() {
+            (None, vec![])
+        } else {
+            (
+                Some(*self.collection_authority_record.key),
+                vec![self.collection_authority_record.clone()],
+            )
+        };
+
+        set_and_verify_sized_collection_item(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.clone(),
+                SetAndVerifySizedCollectionItem {
+                    metadata: self.metadata.clone(),
+                    collection_authority: self.collection_mint.clone(),
+                    payer: self.payer.clone(),
+                    update_authority: self.collection_mint.clone(),
+                    collection_mint: self.collection_mint.clone(),
+                    collection_metadata: self.collection_metadata.clone(),
+                    collection_master_edition: self.collection_master_edition.clone(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts),
+            record,
+        )?;
+
+        let Some(creator) = &self.creator else {
+            return Ok(());
+        };
+
+        debug_msg!("verifying the organizer as creator...");
+
+        sign_metadata(CpiContext::new(
+            self.token_metadata_program.clone(),
+            SignMetadata {
+                creator: creator.clone(),
+                metadata: self.metadata.clone(),
+            },
+        ))
+    }
+}
+
+/// Message signed by an event's attestation authority: attentee, event,
+/// geohash of the attentee's location and the time it was observed.
+struct LocationAttestation {
+    attentee: Pubkey,
+    event: Pubkey,
+    geohash: [u8; ATTESTED_GEOHASH_LENGTH],
+    timestamp: i64,
+}
+
+impl LocationAttestation {
+    const LEN: usize = 32 + 32 + ATTESTED_GEOHASH_LENGTH + 8;
+
+    /// Reads the attestation from the Ed25519 program instruction preceding
+    /// the current one. The precompile has already checked the signature, so
+    /// only the signer and the message are inspected here.
+    fn load(instructions: &AccountInfo, authority: &Pubkey) -> Result<Self> {
+        let current_index = load_current_index_checked(instructions)?;
+        let previous_index = current_index
+            .checked_sub(1)
+            .ok_or(ErrorCode::InvalidAttestation)?;
+        let instruction = load_instruction_at_checked(previous_index as usize, instructions)?;
+
+        if instruction.program_id != ed25519_program::ID {
+            return Err(ErrorCode::InvalidAttestation.into());
+        }
+
+        // one signature, with the key and message in the same instruction
+        let data = &instruction.data;
+        let read_u16 = |offset: usize| -> Result<usize> {
+            data.get(offset..offset + 2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+                .ok_or(ErrorCode::InvalidAttestation.into())
+        };
+
+        if data.first() != Some(&1) {
+            return Err(ErrorCode::InvalidAttestation.into());
+        }
+
+        let public_key_offset = read_u16(6)?;
+        let message_offset = read_u16(10)?;
+        let message_size = read_u16(12)?;
+
+        for instruction_index_offset in [4, 8, 14] {
+            if read_u16(instruction_index_offset)? != u16::MAX as usize {
+                return Err(ErrorCode::InvalidAttestation.into());
+            }
+        }
+
+        let public_key = data
+            .get(public_key_offset..public_key_offset + 32)
+            .ok_or(ErrorCode::InvalidAttestation)?;
+        let message = data
+            .get(message_offset..message_offset + message_size)
+            .ok_or(ErrorCode::InvalidAttestation)?;
+
+        if public_key != authority.as_ref() || message.len() != Self::LEN {
+            return Err(ErrorCode::InvalidAttestation.into());
+        }
+
+        let (attentee, rest) = message.split_at(32);
+        let (event, rest) = rest.split_at(32);
+        let (geohash, timestamp) = rest.split_at(ATTESTED_GEOHASH_LENGTH);
+
+        Ok(Self {
+            attentee: Pubkey::try_from(attentee).map_err(|_| ErrorCode::InvalidAttestation)?,
+            event: Pubkey::try_from(event).map_err(|_| ErrorCode::InvalidAttestation)?,
+            geohash: geohash
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAttestation)?,
+            timestamp: i64::from_le_bytes(
+                timestamp
+                    .try_into()
+                    .map_err(|_| ErrorCode::InvalidAttestation)?,
+            ),
+        })
+    }
+}
+
+fn badge_data(event: &Event, uri: String) -> DataV2 {
+    DataV2 {
+        name: event.name.to_owned() + event.registered_attentees.to_string().as_str(),
+        symbol: event.symbol.clone(),
+        uri,
+        seller_fee_basis_points: event.seller_fee_basis_points,
+        creators: Some(metadata_creators(&event.creators)),
+        collection: None,
+        uses: None,
+    }
+}
+
+/// Checks the symbol, royalty and creator split of an event or series, falling
+/// back to `SYMBOL` and the organizer as sole creator when left empty.
+fn royalty_settings(
+    organizer: Pubkey,
+    symbol: String,
+    seller_fee_basis_points: u16,
+    creators: Vec<EventCreator>,
+) -> Result<(String, Vec<EventCreator>)> {
+    if seller_fee_basis_points > 10_000 {
+        return Err(ErrorCode::InvalidRoyalty.into());
+    }
+
+    let symbol = if symbol.is_empty() {
+        SYMBOL.to_string()
+    } else {
+        symbol
+    };
+
+    if symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(ErrorCode::InvalidSymbol.into());
+    }
+
+    let creators = if creators.is_empty() {
+        vec![EventCreator {
+            address: organizer,
+            share: 100,
+        }]
+    } else {
+        creators
+    };
+
+    let total_share: u16 = creators.iter().map(|creator| creator.share as u16).sum();
+
+    if creators.len() > MAX_CREATORS
+        || total_share != 100
+        || !creators.iter().any(|creator| creator.address == organizer)
+    {
+        return Err(ErrorCode::InvalidCreators.into());
+    }
+
+    Ok((symbol, creators))
+}
+
+fn metadata_creators(creators: &[EventCreator]) -> Vec<Creator> {
+    creators
+        .iter()
+        .map(|creator| Creator {
+            address: creator.address,
+            verified: false,
+            share: creator.share,
+        })
+        .collect()
+}
+
+/// Makes the collection mint PDA a delegated collection authority, then hands
+/// the collection update authority to the creator. Badges keep being verified
+/// by the PDA through the delegate record.
+fn hand_over_collection_authority<'info>(
+    creator: AccountInfo<'info>,
+    collection_mint: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    collection_authority_record: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    approve_collection_authority(
+        CpiContext::new_with_signer(
+            token_metadata_program.clone(),
+            ApproveCollectionAuthority {
+                collection_authority_record,
+                new_collection_authority: collection_mint.clone(),
+                update_authority: collection_mint.clone(),
+                payer: creator.clone(),
+                metadata: metadata.clone(),
+                mint: collection_mint.clone(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(vec![system_program]),
+    )?;
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            token_metadata_program,
+            UpdateMetadataAccountsV2 {
+                metadata,
+                update_authority: collection_mint,
+            },
+            signer_seeds,
+        ),
+        Some(*creator.key),
+        None,
+        None,
+        None,
+    )
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR_SIZE + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Counter>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct InitializeEvent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"event",payer.key().as_ref(),name.as_bytes()],
+        space = ANCHOR_DISCRIMINATOR_SIZE + Event::INIT_SPACE,
+        payer=payer,
+        bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + OrganizerProfile::INIT_SPACE,
+        seeds = [b"organizer", payer.key().as_ref()],
+        bump
+    )]
+    pub organizer_account: Account<'info, OrganizerProfile>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = collection_mint,
+        mint::freeze_authority = collection_mint,
+        seeds = [b"collection_mint".as_ref(),name.as_bytes()],
+        bump
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = collection_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub collection_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct InitializeSeries<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"series",payer.key().as_ref(),name.as_bytes()],
+        space = ANCHOR_DISCRIMINATOR_SIZE + EventSeries::INIT_SPACE,
+        payer=payer,
+        bump
+    )]
+    pub series_account: Account<'info, EventSeries>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = collection_mint,
+        mint::freeze_authority = collection_mint,
+        seeds = [b"series_collection_mint".as_ref(),payer.key().as_ref(),name.as_bytes()],
+        bump
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = collection_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub collection_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct CreateSeriesOccurrence<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"series", creator.key().as_ref(), series_account.name.as_bytes()],
+        bump = series_account.bump,
+        has_one = creator
+    )]
+    pub series_account: Account<'info, EventSeries>,
+
+    #[account(
+        init,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        space = ANCHOR_DISCRIMINATOR_SIZE + Event::INIT_SPACE,
+        payer=creator,
+        bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR_SIZE + OrganizerProfile::INIT_SPACE,
+        seeds = [b"organizer", creator.key().as_ref()],
+        bump
+    )]
+    pub organizer_account: Account<'info, OrganizerProfile>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordSeriesAttendance<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(
+        seeds = [b"series", series_account.creator.as_ref(), series_account.name.as_bytes()],
+        bump = series_account.bump
+    )]
+    pub series_account: Account<'info, EventSeries>,
+
+    #[account(
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        constraint = event_account.series == series_account.key() @ ErrorCode::NotSeriesOccurrence
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(
+        init_if_needed,
+        payer = attentee,
+        space = ANCHOR_DISCRIMINATOR_SIZE + SeriesAttendance::INIT_SPACE,
+        seeds = [b"series_attendance", series_account.key().as_ref(), attentee.key().as_ref()],
+        bump
+    )]
+    pub attendance_account: Account<'info, SeriesAttendance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct EditEvent<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier_id: u8)]
+pub struct CreateTicketTier<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", creator.key().as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR_SIZE + TicketTier::INIT_SPACE,
+        seeds = [b"tier", event_account.key().as_ref(), [tier_id].as_ref()],
+        bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: u8)]
+pub struct CreateSession<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", creator.key().as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR_SIZE + EventSession::INIT_SPACE,
+        seeds = [b"session", event_account.key().as_ref(), [session_id].as_ref()],
+        bump
+    )]
+    pub session_account: Account<'info, EventSession>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetBadgeRequirement<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: u8)]
+pub struct CheckInSession<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        seeds = [b"session", event_account.key().as_ref(), [session_id].as_ref()],
+        bump = session_account.bump
+    )]
+    pub session_account: Account<'info, EventSession>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+}
+
+#[derive(Accounts)]
+pub struct CheckIn<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetEventDeposit<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetCodeRotation<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetGeofence<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct CheckInWithAttestation<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    /// CHECK: instructions sysvar, used to read the Ed25519 attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct TransferCollectionAuthority<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        address = event_account.collection_mint
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:this account is created by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"collection_authority",
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct TransferSeriesCollectionAuthority<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"series",creator.key().as_ref(),name.as_bytes()],
+        bump = series_account.bump,
+        has_one=creator
+    )]
+    pub series_account: Account<'info, EventSeries>,
+
+    #[account(
+        mut,
+        seeds = [b"series_collection_mint".as_ref(),creator.key().as_ref(),series_account.name.as_bytes()],
+        bump = series_account.collection_mint_bump
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:this account is created by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"collection_authority",
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEvent<'info> {
+    /// only the creator may close, the outcome counts towards their profile
+    #[account(mut, address = event_account.creator)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"organizer", event_account.creator.as_ref()],
+        bump = organizer_account.bump
+    )]
+    pub organizer_account: Account<'info, OrganizerProfile>,
+
+    /// Must be empty, deposits are refunded, reclaimed or claimed first.
+    #[account(seeds = [b"vault", event_account.key().as_ref()], bump = event_account.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier_id: u8)]
+pub struct RegisterEvent<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(mut, address = event_account.creator)]
+    pub creator: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", event_account.key().as_ref()],
+        bump = event_account.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tier", event_account.key().as_ref(), [tier_id].as_ref()],
+        bump = tier_account.bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + EventRegistration::INIT_SPACE,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    /// Required when the event has registration questions.
+    #[account(
+        seeds = [b"questions", event_account.key().as_ref()],
+        bump = questions_account.bump
+    )]
+    pub questions_account: Option<Account<'info, RegistrationQuestions>>,
+
+    /// CHECK:the attentee's waitlist entry, passed whether or not they joined
+    /// the waitlist and closed if they did, so registering always takes them
+    /// off it
+    #[account(
+        mut,
+        seeds = [b"waitlist", event_account.key().as_ref(), attentee.key().as_ref()],
+        bump
+    )]
+    pub waitlist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:gets the waitlist entry's rent back when neither `payer` nor the
+    /// attentee paid for it, checked against the entry
+    #[account(mut)]
+    pub waitlist_payer: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWaitlist<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + WaitlistEntry::INIT_SPACE,
+        seeds = [b"waitlist", event_account.key().as_ref(), attentee.key().as_ref()],
+        bump
+    )]
+    pub waitlist_entry: Account<'info, WaitlistEntry>,
+
+    /// CHECK:only checked to be empty, registered attentees have no place on
+    /// the waitlist
+    #[account(
+        seeds = [b"attentee", event_account.key().as_ref(), attentee.key().as_ref()],
+        bump
+    )]
+    pub registration_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveWaitlist<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"waitlist", event_account.key().as_ref(), attentee.key().as_ref()],
+        bump = waitlist_entry.bump,
+        has_one = attentee,
+        has_one = payer
+    )]
+    pub waitlist_entry: Account<'info, WaitlistEntry>,
+
+    /// CHECK: receives the rent of the entry, checked by `has_one`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetEventStats<'info> {
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, merkle_root: [u8; 32], code_count: u32)]
+pub struct SetClaimCodes<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", creator.key().as_ref(), name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR_SIZE + ClaimCodes::space(code_count),
+        seeds = [b"claim_codes", event_account.key().as_ref()],
+        bump
+    )]
+    pub claim_codes_account: Account<'info, ClaimCodes>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetWalkInTier<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", creator.key().as_ref(), name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetEventPaused<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", creator.key().as_ref(), name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct SetRegistrationQuestions<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR_SIZE + RegistrationQuestions::INIT_SPACE,
+        seeds = [b"questions", event_account.key().as_ref()],
+        bump
+    )]
+    pub questions_account: Account<'info, RegistrationQuestions>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProfile<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = attentee,
+        space = ANCHOR_DISCRIMINATOR_SIZE + AttendeeProfile::INIT_SPACE,
+        seeds = [b"profile", attentee.key().as_ref()],
+        bump
+    )]
+    pub profile_account: Account<'info, AttendeeProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProfile<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        close = attentee,
+        seeds = [b"profile", attentee.key().as_ref()],
+        bump = profile_account.bump,
+        has_one = attentee,
+    )]
+    pub profile_account: Account<'info, AttendeeProfile>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRegistration<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = payer,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    /// whoever paid the registration rent gets it back
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tier", event_account.key().as_ref(), [registration_account.tier].as_ref()],
+        bump = tier_account.bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", event_account.key().as_ref()],
+        bump = event_account.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimDeposit<'info> {
+    #[account(mut)]
+    pub attentee: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", event_account.key().as_ref()],
+        bump = event_account.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimForfeitedDeposits<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", creator.key().as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(mut, address = event_account.deposit_beneficiary)]
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", event_account.key().as_ref()],
+        bump = event_account.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStaleRegistration<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds=[b"attentee",event_account.key().as_ref(),registration_account.attentee.as_ref()],
+        bump = registration_account.bump,
+        has_one = payer,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    /// Refunded the deposit if the event was cancelled.
+    #[account(mut, address = registration_account.attentee)]
+    pub attentee: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", event_account.key().as_ref()],
+        bump = event_account.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredEvent<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator,
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"organizer", creator.key().as_ref()],
+        bump = organizer_account.bump
+    )]
+    pub organizer_account: Account<'info, OrganizerProfile>,
+
+    /// Must be empty, deposits are refunded, reclaimed or claimed first.
+    #[account(seeds = [b"vault", event_account.key().as_ref()], bump = event_account.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(name:String)]
+pub struct CancelEvent<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        bump = event_account.bump,
+        has_one=creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"organizer", creator.key().as_ref()],
+        bump = organizer_account.bump
+    )]
+    pub organizer_account: Account<'info, OrganizerProfile>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSponsorPool<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = ANCHOR_DISCRIMINATOR_SIZE + SponsorPool::INIT_SPACE,
+        seeds = [
+            b"sponsor_pool",
+            event_account.key().as_ref(),
+            sponsor.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub sponsor_pool: Account<'info, SponsorPool>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        associated_token::mint = mint,
+        associated_token::authority = sponsor_pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundSponsorPool<'info> {
+    pub funder: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"sponsor_pool",
+            event_account.key().as_ref(),
+            sponsor_pool.sponsor.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = sponsor_pool.bump,
+        has_one = mint
+    )]
+    pub sponsor_pool: Account<'info, SponsorPool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = funder,
+        token::token_program = token_program
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = sponsor_pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSponsorReward<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"sponsor_pool",
+            event_account.key().as_ref(),
+            sponsor_pool.sponsor.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = sponsor_pool.bump,
+        has_one = mint
+    )]
+    pub sponsor_pool: Account<'info, SponsorPool>,
+
+    /// One per pool and registration, so every attentee claims once.
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + SponsorClaim::INIT_SPACE,
+        seeds = [
+            b"sponsor_claim",
+            sponsor_pool.key().as_ref(),
+            registration_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub sponsor_claim: Account<'info, SponsorClaim>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = sponsor_pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = attentee,
+        associated_token::token_program = token_program
+    )]
+    pub attentee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimSponsorPool<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    /// CHECK: the pool's event, which may already be closed. Only read when
+    /// it still exists.
+    #[account(address = sponsor_pool.event)]
+    pub event_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = sponsor,
+        seeds = [
+            b"sponsor_pool",
+            event_account.key().as_ref(),
+            sponsor.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = sponsor_pool.bump,
+        has_one = sponsor,
+        has_one = mint
+    )]
+    pub sponsor_pool: Account<'info, SponsorPool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = sponsor_pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        associated_token::mint = mint,
+        associated_token::authority = sponsor,
+        associated_token::token_program = token_program
+    )]
+    pub sponsor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintNft<'info> {
+    pub attentee: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump = registration_account.bump,
+        has_one = attentee,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(
+        seeds = [b"tier", event_account.key().as_ref(), [registration_account.tier].as_ref()],
+        bump = tier_account.bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    #[account(
+        mut,
+        address = event_account.collection_mint
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"nft_mint".as_ref(),
+            registration_account.key().as_ref()
+        ],
+        bump,
+        mint::decimals = 0,
+        mint::authority=collection_mint,
+        mint::token_program=token_program,
+        mint::freeze_authority=collection_mint
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK:derived and checked by the metadata program
+    #[account(mut)]
+    pub child_nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK:derived and checked by the metadata program
+    #[account(mut)]
+    pub child_nft_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK:derived and checked by the metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:derived and checked by the metadata program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// CHECK:only exists once the collection authority was handed to the creator,
+    /// derived and checked by the metadata program
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority=attentee,
+        associated_token::token_program=token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
-        ctx.accounts.registration_account.attentence_nft_minted = true;
-        ctx.accounts.registration_account.attented = true;
+    /// Required when the event issued claim codes.
+    #[account(
+        mut,
+        seeds = [b"claim_codes", event_account.key().as_ref()],
+        bump = claim_codes_account.bump
+    )]
+    pub claim_codes_account: Option<Account<'info, ClaimCodes>>,
 
-        Ok(())
-    }
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(name:String)]
-pub struct InitializeEvent<'info> {
+#[instruction(tier_id: u8)]
+pub struct WalkInMint<'info> {
+    pub attentee: Signer<'info>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
     #[account(
-        init,
-        seeds = [b"event",payer.key().as_ref(),name.as_bytes()],
-        space = ANCHOR_DISCRIMINATOR_SIZE + Event::INIT_SPACE,
-        payer=payer,
-        bump
+        mut,
+        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump
     )]
     pub event_account: Account<'info, Event>,
 
     #[account(
         init,
         payer = payer,
-        mint::decimals = 0,
-        mint::authority = collection_mint,
-        mint::freeze_authority = collection_mint,
-        seeds = [b"collection_mint".as_ref(),name.as_bytes()],
-        bump
+        space = ANCHOR_DISCRIMINATOR_SIZE + EventRegistration::INIT_SPACE,
+        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
+        bump,
+    )]
+    pub registration_account: Account<'info, EventRegistration>,
+
+    #[account(
+        mut,
+        seeds = [b"tier", event_account.key().as_ref(), [tier_id].as_ref()],
+        bump = tier_account.bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    #[account(
+        mut,
+        address = event_account.collection_mint
     )]
     pub collection_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
-        payer=payer,
-        token::mint = collection_mint,
-        token::authority = collection_token_account,
-        seeds = [b"collection_associated_token".as_ref(),name.as_bytes()],
-        bump
+        payer = payer,
+        seeds = [
+            b"nft_mint".as_ref(),
+            registration_account.key().as_ref()
+        ],
+        bump,
+        mint::decimals = 0,
+        mint::authority=collection_mint,
+        mint::token_program=token_program,
+        mint::freeze_authority=collection_mint
     )]
-    pub collection_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK:this account is checked by metadata smart contract
     #[account(
         mut,
         seeds=[
             b"metadata",
             token_metadata_program.key().as_ref(),
-            collection_mint.key().as_ref()
+            nft_mint.key().as_ref()
         ],
         bump,
         seeds::program = token_metadata_program.key()
     )]
-    pub metadata: UncheckedAccount<'info>,
+    ///CHECK:this account is checked by metadata smart contract
+    pub child_nft_metadata: UncheckedAccount<'info>,
 
-    /// CHECK:this account is checked by metadata smart contract
+    ///CHECK:this account is checked by metadata smart contract
     #[account(
         mut,
         seeds=[
             b"metadata",
             token_metadata_program.key().as_ref(),
-            collection_mint.key().as_ref(),
+            nft_mint.key().as_ref(),
             b"edition"
         ],
         bump,
         seeds::program = token_metadata_program.key()
     )]
-    pub master_edition: UncheckedAccount<'info>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_metadata_program: Program<'info, Metadata>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-#[instruction(name:String)]
-pub struct EditEvent<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    pub child_nft_master_edition: UncheckedAccount<'info>,
 
+    /// CHECK:this account is checked by metadata smart contract
     #[account(
         mut,
-        seeds = [b"event",creator.key().as_ref(),name.as_bytes()],
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
         bump,
-        has_one=creator
+        seeds::program = token_metadata_program.key()
     )]
-    pub event_account: Account<'info, Event>,
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct CloseEvent<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    pub metadata: UncheckedAccount<'info>,
 
+    /// CHECK:this account is checked by metadata smart contract
     #[account(
         mut,
-        close = payer,
-        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
-        bump
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
     )]
-    pub event_account: Account<'info, Event>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct RegisterEvent<'info> {
-    #[account(mut)]
-    pub attentee: Signer<'info>,
+    pub master_edition: UncheckedAccount<'info>,
 
+    /// CHECK:only exists once the collection authority was handed to the creator
     #[account(
-        mut,
-        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
-        bump
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"collection_authority",
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
     )]
-    pub event_account: Account<'info, Event>,
+    pub collection_authority_record: UncheckedAccount<'info>,
 
     #[account(
         init,
-        payer= attentee,
-        space = ANCHOR_DISCRIMINATOR_SIZE + EventRegistration::INIT_SPACE,
-        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
-        bump,
+        payer=payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority=attentee,
+        associated_token::token_program=token_program
     )]
-    pub registration_account: Account<'info, EventRegistration>,
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct CancelRegistration<'info> {
+pub struct AirdropBadge<'info> {
     #[account(mut)]
-    pub attentee: Signer<'info>,
+    pub creator: Signer<'info>,
+
+    pub attentee: SystemAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
-        bump
+        seeds = [b"event", creator.key().as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
     )]
     pub event_account: Account<'info, Event>,
 
     #[account(
         mut,
-        close = attentee,
         seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
-        bump,
+        bump = registration_account.bump,
+        has_one = attentee,
     )]
     pub registration_account: Account<'info, EventRegistration>,
 
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct MintNft<'info> {
-    #[account(mut)]
-    pub attentee: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"event", event_account.creator.as_ref(), event_account.name.as_bytes()],
-        bump
-    )]
-    pub event_account: Account<'info, Event>,
-
     #[account(
-        mut,
-        seeds=[b"attentee",event_account.key().as_ref(),attentee.key().as_ref()],
-        bump,
-        has_one = attentee,
+        seeds = [b"tier", event_account.key().as_ref(), [registration_account.tier].as_ref()],
+        bump = tier_account.bump
     )]
-    pub registration_account: Account<'info, EventRegistration>,
+    pub tier_account: Account<'info, TicketTier>,
 
     #[account(
         mut,
-        seeds = [b"collection_mint".as_ref(),event_account.name.as_bytes()],
-        bump
+        address = event_account.collection_mint
     )]
     pub collection_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
-        payer = attentee,
+        payer = creator,
         seeds = [
             b"nft_mint".as_ref(),
             registration_account.key().as_ref()
@@ -575,7 +4025,92 @@ pub struct MintNft<'info> {
         bump,
         seeds::program = token_metadata_program.key()
     )]
-    pub child_nft_master_edition: UncheckedAccount<'info>,
+    pub child_nft_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK:this account is checked by metadata smart contract
+    #[account(
+        mut,
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// CHECK:only exists once the collection authority was handed to the creator
+    #[account(
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"collection_authority",
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=creator,
+        associated_token::mint = nft_mint,
+        associated_token::authority=attentee,
+        associated_token::token_program=token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AirdropBadges<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", creator.key().as_ref(), event_account.name.as_bytes()],
+        bump = event_account.bump,
+        has_one = creator
+    )]
+    pub event_account: Account<'info, Event>,
+
+    #[account(
+        seeds = [b"tier", event_account.key().as_ref(), [tier_account.tier_id].as_ref()],
+        bump = tier_account.bump
+    )]
+    pub tier_account: Account<'info, TicketTier>,
+
+    #[account(
+        mut,
+        address = event_account.collection_mint
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
 
     /// CHECK:this account is checked by metadata smart contract
     #[account(
@@ -604,14 +4139,22 @@ pub struct MintNft<'info> {
     )]
     pub master_edition: UncheckedAccount<'info>,
 
+    /// CHECK:only exists once the collection authority was handed to the creator
     #[account(
-        init,
-        payer=attentee,
-        associated_token::mint = nft_mint,
-        associated_token::authority=attentee,
-        associated_token::token_program=token_program
+        seeds=[
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            collection_mint.key().as_ref(),
+            b"collection_authority",
+            collection_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
     )]
-    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -633,9 +4176,268 @@ pub struct Event {
     pub attentance_code: [u8; 32],
     pub start_time: i64,
     pub end_time: i64,
-    pub total_attentees: u32,
-    pub registered_attentees: u32,
+    pub total_attentees: u64,
+    pub registered_attentees: u64,
+    pub open_registrations: u64,
     pub collection_mint: Pubkey,
+    pub session_count: u8,
+    pub min_sessions_for_badge: u8,
+    #[max_len(32)]
+    pub collection_name: String,
+    pub series: Pubkey,
+    pub occurrence: u32,
+    pub deposit_lamports: u64,
+    pub deposit_beneficiary: Pubkey,
+    pub deposit_claim_deadline: i64,
+    /// Latest `refund_deadline` of the event's sponsor pools.
+    pub sponsor_refund_deadline: i64,
+    #[max_len(10)]
+    pub symbol: String,
+    pub seller_fee_basis_points: u16,
+    #[max_len(5)]
+    pub creators: Vec<EventCreator>,
+    #[max_len(6)]
+    pub geofence: String,
+    pub attestation_authority: Pubkey,
+    pub code_rotation_period: u32,
+    pub code_checkpoint_window: u32,
+    pub failed_code_attempts: u64,
+    pub question_count: u8,
+    pub checked_in_attentees: u64,
+    pub minted_attentees: u64,
+    pub cancelled_registrations: u64,
+    pub waitlisted_attentees: u64,
+    pub cancelled: bool,
+    /// Set by the creator to stop registrations, mints and payouts.
+    pub paused: bool,
+    /// Free tier that unregistered wallets join through `walk_in_mint`.
+    pub walk_in_tier: Option<u8>,
+    /// Single-use claim codes issued, badges need one of them once set.
+    pub claim_code_count: u32,
+    pub bump: u8,
+    pub collection_mint_bump: u8,
+    pub vault_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EventCreator {
+    pub address: Pubkey,
+    pub share: u8,
+}
+
+impl Event {
+    /// Signer seeds of the collection mint, the mint and freeze authority of
+    /// every badge. Occurrences of a series share the series' mint, which is
+    /// scoped to the series creator.
+    pub fn collection_mint_seeds(&self) -> Vec<&[u8]> {
+        let bump = std::slice::from_ref(&self.collection_mint_bump);
+
+        if self.series == Pubkey::default() {
+            vec![b"collection_mint", self.collection_name.as_bytes(), bump]
+        } else {
+            vec![
+                b"series_collection_mint",
+                self.creator.as_ref(),
+                self.collection_name.as_bytes(),
+                bump,
+            ]
+        }
+    }
+
+    pub fn stats(&self) -> EventStats {
+        EventStats {
+            capacity: self.total_attentees,
+            registered: self.registered_attentees,
+            checked_in: self.checked_in_attentees,
+            minted: self.minted_attentees,
+            cancelled: self.cancelled_registrations,
+            waitlisted: self.waitlisted_attentees,
+            open_registrations: self.open_registrations,
+        }
+    }
+
+    /// When registrations and the event may be cranked closed: after the
+    /// grace period, and not while attentees can still reclaim deposits or
+    /// claim sponsor rewards.
+    pub fn cleanup_time(&self) -> Result<i64> {
+        let grace_period_end = self
+            .end_time
+            .checked_add(CLEANUP_GRACE_PERIOD)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(grace_period_end
+            .max(self.deposit_claim_deadline)
+            .max(self.sponsor_refund_deadline))
+    }
+
+    /// Rejects a rotation period that splits the event into more than
+    /// `MAX_CODE_WINDOWS` windows.
+    pub fn check_code_windows(&self) -> Result<()> {
+        if self.code_rotation_period == 0 {
+            return Ok(());
+        }
+
+        let windows = self.end_time.saturating_sub(self.start_time)
+            / i64::from(self.code_rotation_period)
+            + 1;
+        if windows > i64::from(MAX_CODE_WINDOWS) {
+            return Err(ErrorCode::InvalidCodeRotation.into());
+        }
+        Ok(())
+    }
+
+    /// Checks an attendance code, returning whether it matched. Static codes
+    /// are valid for the whole event, rotating ones only during their window
+    /// or the one before. With rotation, `attentance_code` holds the newest
+    /// verified code of the hash chain, so later checks only hash back as far
+    /// as that checkpoint.
+    pub fn verify_attentance_code(&mut self, attentance_code: [u8; 32], now: i64) -> Result<bool> {
+        if now < self.start_time || now > self.end_time {
+            return Err(ErrorCode::CheckInNotOpen.into());
+        }
+
+        if self.code_rotation_period == 0 {
+            return Ok(attentance_code == self.attentance_code);
+        }
+
+        let current_window =
+            ((now - self.start_time) / self.code_rotation_period as i64) as u32 + 1;
+        let checkpoint = self.code_checkpoint_window;
+
+        // window 0 is the commitment itself, not a code
+        for window in [current_window, current_window - 1] {
+            let matches = window > 0
+                && if window >= checkpoint {
+                    hash_chain(attentance_code, window - checkpoint) == self.attentance_code
+                } else {
+                    hash_chain(self.attentance_code, checkpoint - window) == attentance_code
+                };
+
+            if matches {
+                if window > checkpoint {
+                    self.attentance_code = attentance_code;
+                    self.code_checkpoint_window = window;
+                }
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// The vault is a plain system account that never signs for its own
+/// creation, so its bump is derived once when the event is created.
+fn vault_bump(event: &Pubkey) -> u8 {
+    Pubkey::find_program_address(&[b"vault", event.as_ref()], &crate::ID).1
+}
+
+/// Closes a program account that was not loaded as an `Account`, the way
+/// a `close` constraint would, moving its rent to `destination`.
+fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent = account.lamports();
+    account.sub_lamports(rent)?;
+    destination.add_lamports(rent)?;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+fn mark_minted(event: &mut Event, registration: &mut EventRegistration) -> Result<()> {
+    registration.attentence_nft_minted = true;
+    event.minted_attentees = event
+        .minted_attentees
+        .checked_add(1)
+        .ok_or(ErrorCode::OverflowError)?;
+    Ok(())
+}
+
+/// Marks a registration as checked in, counting each attentee once.
+fn mark_checked_in(event: &mut Event, registration: &mut EventRegistration) -> Result<()> {
+    if !registration.attented {
+        registration.attented = true;
+        event.checked_in_attentees = event
+            .checked_in_attentees
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+    }
+    Ok(())
+}
+
+/// Registration answers must follow the event's questions one to one, with
+/// `Skipped` only allowed for optional questions.
+fn check_answers(
+    event: &Event,
+    questions: Option<&RegistrationQuestions>,
+    answers: &[RegistrationAnswer],
+) -> Result<()> {
+    let questions = match questions {
+        Some(questions_account) => questions_account.questions.as_slice(),
+        None if event.question_count == 0 => &[],
+        None => return Err(ErrorCode::InvalidAnswers.into()),
+    };
+
+    if answers.len() != questions.len() {
+        return Err(ErrorCode::InvalidAnswers.into());
+    }
+
+    for (question, answer) in questions.iter().zip(answers) {
+        let valid = match (answer, question.kind) {
+            (RegistrationAnswer::Skipped, _) => !question.required,
+            (RegistrationAnswer::TextHash(_), QuestionKind::Text) => true,
+            (RegistrationAnswer::Choice(choice), QuestionKind::Choice) => {
+                *choice < question.choices
+            }
+            (RegistrationAnswer::Number(_), QuestionKind::Number) => true,
+            _ => false,
+        };
+
+        if !valid {
+            return Err(ErrorCode::InvalidAnswers.into());
+        }
+    }
+    Ok(())
+}
+
+/// Counts a wrong attendance code against the registration and the event,
+/// locking the registration out once it reaches `MAX_FAILED_CODE_ATTEMPTS`.
+/// Returns `Ok` so the counters are not rolled back with the transaction.
+fn record_failed_code_attempt(
+    event_account: &mut Account<Event>,
+    registration_account: &mut Account<EventRegistration>,
+    now: i64,
+) -> Result<()> {
+    event_account.failed_code_attempts = event_account
+        .failed_code_attempts
+        .checked_add(1)
+        .ok_or(ErrorCode::OverflowError)?;
+    registration_account.failed_code_attempts =
+        registration_account.failed_code_attempts.saturating_add(1);
+
+    if registration_account.failed_code_attempts >= MAX_FAILED_CODE_ATTEMPTS {
+        registration_account.failed_code_attempts = 0;
+        registration_account.code_locked_until = now
+            .checked_add(CODE_LOCKOUT_PERIOD)
+            .ok_or(ErrorCode::OverflowError)?;
+    }
+
+    emit!(AttendanceCodeRejected {
+        event: event_account.key(),
+        attentee: registration_account.attentee,
+        registration: registration_account.key(),
+        event_failed_attempts: event_account.failed_code_attempts,
+        locked_until: registration_account.code_locked_until,
+    });
+    Ok(())
+}
+
+fn hash_chain(mut value: [u8; 32], steps: u32) -> [u8; 32] {
+    for _ in 0..steps {
+        value = hash(&value).to_bytes();
+    }
+    value
 }
 
 #[account]
@@ -643,9 +4445,458 @@ pub struct Event {
 pub struct EventRegistration {
     pub event: Pubkey,
     pub attentee: Pubkey,
+    pub payer: Pubkey,
+    pub tier: u8,
     pub registered: bool,
     pub attented: bool,
     pub attentence_nft_minted: bool,
+    pub sessions_attended: u64,
+    pub series_attendance_recorded: bool,
+    pub deposit_paid: u64,
+    pub failed_code_attempts: u8,
+    pub code_locked_until: i64,
+    #[max_len(4)]
+    pub answers: Vec<RegistrationAnswer>,
+    pub bump: u8,
+}
+
+impl EventRegistration {
+    pub fn check_code_lockout(&self, now: i64) -> Result<()> {
+        if now < self.code_locked_until {
+            return Err(ErrorCode::CodeLockedOut.into());
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TicketTier {
+    pub event: Pubkey,
+    pub tier_id: u8,
+    #[max_len(16)]
+    pub name: String,
+    pub price: u64,
+    pub capacity: u64,
+    pub registered_attentees: u64,
+    pub registration_start: i64,
+    pub registration_end: i64,
+    #[max_len(64)]
+    pub uri: String,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct EventSession {
+    pub event: Pubkey,
+    pub session_id: u8,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub attentance_code: [u8; 32],
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct EventSeries {
+    pub creator: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(64)]
+    pub description: String,
+    #[max_len(64)]
+    pub url: String,
+    pub collection_mint: Pubkey,
+    pub collection_mint_bump: u8,
+    pub occurrence_count: u32,
+    #[max_len(10)]
+    pub symbol: String,
+    pub seller_fee_basis_points: u16,
+    #[max_len(5)]
+    pub creators: Vec<EventCreator>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SeriesAttendance {
+    pub series: Pubkey,
+    pub attentee: Pubkey,
+    pub occurrences_attended: u32,
+    pub last_occurrence: u32,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub bump: u8,
+}
+
+/// Track record of an organizer across all their events.
+#[account]
+#[derive(InitSpace)]
+pub struct OrganizerProfile {
+    pub organizer: Pubkey,
+    pub events_hosted: u64,
+    pub events_completed: u64,
+    pub events_cancelled: u64,
+    /// Registrations and check-ins of completed and cancelled events.
+    pub total_registrations: u64,
+    pub total_checked_in: u64,
+    pub bump: u8,
+}
+
+impl OrganizerProfile {
+    /// Share of registrations that checked in, in basis points.
+    pub fn check_in_rate_bps(&self) -> Option<u16> {
+        (self.total_registrations > 0).then(|| {
+            (self.total_checked_in.min(self.total_registrations) * 10_000
+                / self.total_registrations) as u16
+        })
+    }
+
+    fn record_hosted(&mut self, organizer: Pubkey, bump: u8) -> Result<()> {
+        self.organizer = organizer;
+        self.bump = bump;
+        self.events_hosted = self
+            .events_hosted
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(())
+    }
+
+    fn record_outcome(&mut self, event: &Event, cancelled: bool) -> Result<()> {
+        if cancelled {
+            self.events_cancelled = self
+                .events_cancelled
+                .checked_add(1)
+                .ok_or(ErrorCode::OverflowError)?;
+        } else {
+            self.events_completed = self
+                .events_completed
+                .checked_add(1)
+                .ok_or(ErrorCode::OverflowError)?;
+        }
+
+        self.total_registrations = self
+            .total_registrations
+            .checked_add(event.registered_attentees)
+            .ok_or(ErrorCode::OverflowError)?;
+        self.total_checked_in = self
+            .total_checked_in
+            .checked_add(event.checked_in_attentees)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(())
+    }
+}
+
+/// Program wide settings for the hosted service, a singleton at `[b"config"]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Cut of every paid ticket sent to `treasury`.
+    pub fee_basis_points: u16,
+    pub treasury: Pubkey,
+    pub paused: bool,
+    /// Largest `total_attentees` an event may have.
+    pub max_event_size: u64,
+    pub bump: u8,
+}
+
+impl Config {
+    fn validate(&self) -> Result<()> {
+        if self.fee_basis_points > MAX_PLATFORM_FEE_BASIS_POINTS || self.max_event_size == 0 {
+            return Err(ErrorCode::InvalidConfig.into());
+        }
+        Ok(())
+    }
+
+    fn check_new_event(&self, total_attentees: u64) -> Result<()> {
+        if self.paused {
+            return Err(ErrorCode::ProgramPaused.into());
+        }
+        if total_attentees > self.max_event_size {
+            return Err(ErrorCode::EventTooLarge.into());
+        }
+        Ok(())
+    }
+
+    fn check_not_paused(&self, event: &Event) -> Result<()> {
+        if self.paused {
+            return Err(ErrorCode::ProgramPaused.into());
+        }
+        if event.paused {
+            return Err(ErrorCode::EventPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Platform fee on a ticket, rounded down.
+    pub fn platform_fee(&self, price: u64) -> u64 {
+        (u128::from(price) * u128::from(self.fee_basis_points) / 10_000) as u64
+    }
+}
+
+/// SPL tokens put up by a sponsor for the attentees of an event, held by the
+/// pool's associated token account.
+#[account]
+#[derive(InitSpace)]
+pub struct SponsorPool {
+    pub event: Pubkey,
+    pub sponsor: Pubkey,
+    pub mint: Pubkey,
+    /// Fixed reward per attentee, zero to split the pool equally.
+    pub amount_per_attentee: u64,
+    /// Claims close and the sponsor can take back the rest after this.
+    pub refund_deadline: i64,
+    pub deposited: u64,
+    pub claimed: u64,
+    pub claimants: u32,
+    /// Equal split, fixed by the first claim so later cancellations do not
+    /// shift it.
+    pub equal_share: u64,
+    pub bump: u8,
+}
+
+impl SponsorPool {
+    fn reward(&mut self, checked_in_attentees: u64) -> Result<u64> {
+        let amount = if self.amount_per_attentee > 0 {
+            self.amount_per_attentee
+        } else {
+            if self.claimants == 0 {
+                self.equal_share = self.deposited / checked_in_attentees.max(1);
+            }
+            self.equal_share
+        };
+
+        let remaining = self
+            .deposited
+            .checked_sub(self.claimed)
+            .ok_or(ErrorCode::OverflowError)?;
+
+        if amount == 0 || amount > remaining {
+            return Err(ErrorCode::SponsorPoolEmpty.into());
+        }
+
+        self.claimed = self
+            .claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::OverflowError)?;
+        self.claimants = self
+            .claimants
+            .checked_add(1)
+            .ok_or(ErrorCode::OverflowError)?;
+        Ok(amount)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SponsorClaim {
+    pub pool: Pubkey,
+    pub registration: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WaitlistEntry {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub payer: Pubkey,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EventStats {
+    pub capacity: u64,
+    pub registered: u64,
+    pub checked_in: u64,
+    pub minted: u64,
+    pub cancelled: u64,
+    pub waitlisted: u64,
+    pub open_registrations: u64,
+}
+
+/// Single-use claim codes of an event, a merkle root and a bitmap of the
+/// codes already spent.
+#[account]
+pub struct ClaimCodes {
+    pub event: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub code_count: u32,
+    pub spent: Vec<u8>,
+    pub bump: u8,
+}
+
+impl ClaimCodes {
+    pub fn space(code_count: u32) -> usize {
+        32 + 32 + 4 + 4 + code_count.div_ceil(8) as usize + 1
+    }
+
+    pub fn leaf(index: u32, code: &[u8; 32]) -> [u8; 32] {
+        hashv(&[b"claim_code", &index.to_le_bytes(), code]).to_bytes()
+    }
+
+    fn spend(&mut self, claim_code: &ClaimCode) -> Result<()> {
+        if claim_code.index >= self.code_count {
+            return Err(ErrorCode::InvalidClaimCode.into());
+        }
+
+        let root = claim_code.proof.iter().fold(
+            Self::leaf(claim_code.index, &claim_code.code),
+            |node, sibling| {
+                if node <= *sibling {
+                    hashv(&[&node, sibling]).to_bytes()
+                } else {
+                    hashv(&[sibling, &node]).to_bytes()
+                }
+            },
+        );
+
+        if root != self.merkle_root {
+            return Err(ErrorCode::InvalidClaimCode.into());
+        }
+
+        let byte = &mut self.spent[(claim_code.index / 8) as usize];
+        let bit = 1u8 << (claim_code.index % 8);
+
+        if *byte & bit != 0 {
+            return Err(ErrorCode::ClaimCodeSpent.into());
+        }
+        *byte |= bit;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimCode {
+    pub index: u32,
+    pub code: [u8; 32],
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RegistrationQuestions {
+    pub event: Pubkey,
+    #[max_len(4)]
+    pub questions: Vec<RegistrationQuestion>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RegistrationQuestion {
+    pub kind: QuestionKind,
+    #[max_len(64)]
+    pub prompt: String,
+    /// Number of options of a `Choice` question, listed in the prompt.
+    pub choices: u8,
+    pub required: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuestionKind {
+    Text,
+    Choice,
+    Number,
+}
+
+/// Text answers are stored as a sha256 hash, so the organizer can match what
+/// was submitted off chain without personal data ending up on chain.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum RegistrationAnswer {
+    Skipped,
+    TextHash([u8; 32]),
+    Choice(u8),
+    Number(u64),
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AttendeeProfile {
+    pub attentee: Pubkey,
+    #[max_len(32)]
+    pub display_name: String,
+    /// sha256 of the attentee's contact details, shared with organizers off chain.
+    pub contact_hash: [u8; 32],
+    #[max_len(32)]
+    pub social_handle: String,
+    /// Flags defined by the app, such as sharing contact details with organizers.
+    pub preferences: u32,
+    pub bump: u8,
+}
+
+#[event]
+pub struct EventCreated {
+    pub event: Pubkey,
+    pub creator: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_attentees: u64,
+}
+
+#[event]
+pub struct EventCancelled {
+    pub event: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AttendeeRegistered {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub registration: Pubkey,
+    pub tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RegistrationCancelled {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub registration: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AttendeeCheckedIn {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub registration: Pubkey,
+    /// `None` for event level check-ins.
+    pub session_id: Option<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BadgeMinted {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub registration: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SponsorRewardClaimed {
+    pub pool: Pubkey,
+    pub attentee: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AttendanceCodeRejected {
+    pub event: Pubkey,
+    pub attentee: Pubkey,
+    pub registration: Pubkey,
+    pub event_failed_attempts: u64,
+    /// Set once the registration is locked out.
+    pub locked_until: i64,
 }
 
 #[error_code]
@@ -664,4 +4915,118 @@ pub enum ErrorCode {
     OverflowError,
     #[msg("invalid event start or end time")]
     InvalidEventTime,
+    #[msg("invalid tier registration window")]
+    InvalidTierWindow,
+    #[msg("tier name or uri is too long")]
+    InvalidTierMetadata,
+    #[msg("tier registration is closed")]
+    TierRegistrationClosed,
+    #[msg("tier sold out")]
+    TierSoldOut,
+    #[msg("invalid session id")]
+    InvalidSessionId,
+    #[msg("session is not active")]
+    SessionNotActive,
+    #[msg("session already attended")]
+    SessionAlreadyAttended,
+    #[msg("not enough sessions attended")]
+    NotEnoughSessionsAttended,
+    #[msg("event is not an occurrence of this series")]
+    NotSeriesOccurrence,
+    #[msg("attentee has not attended the event")]
+    NotAttended,
+    #[msg("series attendance already recorded")]
+    SeriesAttendanceAlreadyRecorded,
+    #[msg("event cleanup grace period has not passed")]
+    CleanupNotAllowedYet,
+    #[msg("event still has open registrations")]
+    EventNotEmpty,
+    #[msg("event vault still holds deposits")]
+    VaultNotEmpty,
+    #[msg("deposit cannot change once registrations exist")]
+    DepositLocked,
+    #[msg("deposit claim deadline must be after the event ends")]
+    InvalidDepositDeadline,
+    #[msg("deposit is below the rent exempt minimum")]
+    DepositTooSmall,
+    #[msg("deposit claim window is closed")]
+    DepositClaimClosed,
+    #[msg("no deposit to claim")]
+    NoDepositToClaim,
+    #[msg("royalty basis points must not exceed 10000")]
+    InvalidRoyalty,
+    #[msg("symbol is too long")]
+    InvalidSymbol,
+    #[msg("creators must include the organizer and shares must add up to 100")]
+    InvalidCreators,
+    #[msg("invalid airdrop accounts")]
+    InvalidAirdropAccounts,
+    #[msg("invalid geofence")]
+    InvalidGeofence,
+    #[msg("event has no geofence")]
+    NoGeofence,
+    #[msg("check-in is not open")]
+    CheckInNotOpen,
+    #[msg("invalid location attestation")]
+    InvalidAttestation,
+    #[msg("location is outside the event geofence")]
+    OutsideGeofence,
+    #[msg("location attestation expired")]
+    AttestationExpired,
+    #[msg("already checked in")]
+    AlreadyCheckedIn,
+    #[msg("code rotation can only be set before the event starts, with a period of at least 10 seconds")]
+    InvalidCodeRotation,
+    #[msg("event requires a location attestation to check in")]
+    AttestationRequired,
+    #[msg("too many wrong attendance codes, try again later")]
+    CodeLockedOut,
+    #[msg("registration questions cannot change once registrations exist")]
+    QuestionsLocked,
+    #[msg("invalid registration questions")]
+    InvalidQuestions,
+    #[msg("answers do not match the registration questions")]
+    InvalidAnswers,
+    #[msg("profile field is too long")]
+    InvalidProfile,
+    #[msg("event is cancelled")]
+    EventCancelled,
+    #[msg("event has already ended")]
+    EventAlreadyEnded,
+    #[msg("signer is not the program admin")]
+    Unauthorized,
+    #[msg("platform fee must not exceed 1000 basis points and max event size must be positive")]
+    InvalidConfig,
+    #[msg("program is paused")]
+    ProgramPaused,
+    #[msg("event is larger than the platform allows")]
+    EventTooLarge,
+    #[msg("event is paused")]
+    EventPaused,
+    #[msg("claim codes must be between 1 and 16384")]
+    InvalidClaimCodes,
+    #[msg("event requires a claim code")]
+    ClaimCodeRequired,
+    #[msg("invalid claim code")]
+    InvalidClaimCode,
+    #[msg("claim code already used")]
+    ClaimCodeSpent,
+    #[msg("event still has free spots")]
+    EventNotFull,
+    #[msg("walk-ins are not allowed for this tier")]
+    WalkInNotAllowed,
+    #[msg("sponsor refund deadline must be after the event ends")]
+    InvalidSponsorPool,
+    #[msg("sponsor pool no longer accepts deposits")]
+    SponsorPoolClosed,
+    #[msg("sponsor rewards can only be claimed between the event end and the refund deadline")]
+    SponsorClaimClosed,
+    #[msg("sponsor pool has not enough tokens left")]
+    SponsorPoolEmpty,
+    #[msg("sponsor pool cannot be reclaimed before the refund deadline")]
+    SponsorRefundNotOpen,
+    #[msg("waitlist entry rent must go back to the wallet that paid it")]
+    WaitlistPayerMismatch,
+    #[msg("attentee is already registered")]
+    AlreadyRegistered,
 }
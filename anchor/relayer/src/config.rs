@@ -0,0 +1,72 @@
+use std::{env, str::FromStr, time::Duration};
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+};
+
+use crate::error::RelayError;
+
+/// Relayer settings, read from the environment:
+///
+/// - `RELAYER_KEYPAIR`: fee payer keypair file (required)
+/// - `RELAYER_ALLOWED_CREATORS`: comma separated organizer keys (required)
+/// - `RELAYER_RPC_URL`: defaults to the local validator
+/// - `RELAYER_BIND_ADDR`: defaults to `127.0.0.1:8080`
+/// - `RELAYER_RATE_LIMIT_WINDOW_SECS`: defaults to one hour
+/// - `RELAYER_MAX_PER_WALLET`: sponsored calls per wallet and window, defaults to 10
+/// - `RELAYER_MAX_PER_WALLET_EVENT`: sponsored calls per wallet, event and window, defaults to 2
+/// - `RELAYER_MAX_PER_EVENT`: sponsored calls per event and window across all wallets, defaults to 500
+pub struct Config {
+    pub rpc_url: String,
+    pub bind_addr: String,
+    pub fee_payer: Keypair,
+    pub allowed_creators: Vec<Pubkey>,
+    pub rate_limit_window: Duration,
+    pub max_per_wallet: u32,
+    pub max_per_wallet_event: u32,
+    pub max_per_event: u32,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, RelayError> {
+        let keypair_path = required("RELAYER_KEYPAIR")?;
+        let fee_payer = read_keypair_file(&keypair_path)
+            .map_err(|err| RelayError::Config(format!("RELAYER_KEYPAIR: {err}")))?;
+
+        let allowed_creators = required("RELAYER_ALLOWED_CREATORS")?
+            .split(',')
+            .map(|key| {
+                Pubkey::from_str(key.trim())
+                    .map_err(|_| RelayError::Config(format!("invalid creator key {key}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            rpc_url: optional("RELAYER_RPC_URL", "http://127.0.0.1:8899".to_string())?,
+            bind_addr: optional("RELAYER_BIND_ADDR", "127.0.0.1:8080".to_string())?,
+            fee_payer,
+            allowed_creators,
+            rate_limit_window: Duration::from_secs(optional(
+                "RELAYER_RATE_LIMIT_WINDOW_SECS",
+                3600,
+            )?),
+            max_per_wallet: optional("RELAYER_MAX_PER_WALLET", 10)?,
+            max_per_wallet_event: optional("RELAYER_MAX_PER_WALLET_EVENT", 2)?,
+            max_per_event: optional("RELAYER_MAX_PER_EVENT", 500)?,
+        })
+    }
+}
+
+fn required(name: &str) -> Result<String, RelayError> {
+    env::var(name).map_err(|_| RelayError::Config(format!("{name} is not set")))
+}
+
+fn optional<T: FromStr>(name: &str, default: T) -> Result<T, RelayError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| RelayError::Config(format!("invalid value for {name}"))),
+        Err(_) => Ok(default),
+    }
+}
@@ -0,0 +1,56 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use solana_client::client_error::ClientError;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    #[error("invalid config: {0}")]
+    Config(String),
+    #[error("transaction could not be decoded")]
+    Decode,
+    #[error("fee payer must be the relayer")]
+    FeePayer,
+    #[error("missing or invalid signature for {0}")]
+    Signature(Pubkey),
+    #[error("instruction {0} is not allowed")]
+    Instruction(usize),
+    #[error("unexpected accounts in instruction {0}")]
+    Accounts(usize),
+    #[error("event {0} is not sponsored")]
+    EventNotSponsored(Pubkey),
+    #[error("rate limit exceeded for {0}")]
+    RateLimited(Pubkey),
+    #[error("sponsored call budget of event {0} is used up")]
+    EventBudgetExhausted(Pubkey),
+    #[error("transaction failed in simulation: {0}")]
+    Simulation(String),
+    #[error("rpc error: {0}")]
+    Rpc(#[from] Box<ClientError>),
+}
+
+impl From<ClientError> for RelayError {
+    fn from(err: ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+impl IntoResponse for RelayError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::RateLimited(_) | Self::EventBudgetExhausted(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Rpc(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::BAD_REQUEST,
+        };
+
+        (
+            status,
+            Json(serde_json::json!({ "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
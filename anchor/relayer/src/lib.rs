@@ -0,0 +1,118 @@
+//! Fee payer relayer for sponsored transactions.
+//!
+//! Attendees sign `register_event` / `check_in` / `mint_nft` / `walk_in_mint`
+//! transactions with the relayer set as fee payer (and `payer` where rent is
+//! due), post them to `/relay`, and the relayer co-signs and submits them once
+//! they pass validation, simulation and rate limiting.
+
+pub mod config;
+pub mod error;
+pub mod rate_limit;
+pub mod server;
+pub mod validate;
+
+use anchor_lang::AccountDeserialize;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+};
+
+use crate::{
+    config::Config, error::RelayError, rate_limit::RateLimiter, validate::validate_transaction,
+};
+
+pub struct Relayer {
+    config: Config,
+    rpc: RpcClient,
+    rate_limiter: RateLimiter,
+}
+
+impl Relayer {
+    pub fn new(config: Config) -> Self {
+        let rpc =
+            RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+        let rate_limiter = RateLimiter::new(
+            config.rate_limit_window,
+            config.max_per_wallet,
+            config.max_per_wallet_event,
+            config.max_per_event,
+        );
+
+        Self {
+            config,
+            rpc,
+            rate_limiter,
+        }
+    }
+
+    pub fn fee_payer(&self) -> Pubkey {
+        self.config.fee_payer.pubkey()
+    }
+
+    /// Validates, co-signs and submits a base64 encoded transaction.
+    pub async fn relay(&self, encoded: &str) -> Result<Signature, RelayError> {
+        let mut transaction = validate::decode_transaction(encoded)?;
+        let calls = validate_transaction(&transaction, &self.fee_payer())?;
+
+        for call in &calls {
+            self.check_event(&call.event).await?;
+        }
+
+        // transactions that would fail still cost the fee payer its fee, so
+        // they are dropped before they count against any limit
+        let simulation = self
+            .rpc
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .await?;
+
+        if let Some(err) = simulation.value.err {
+            return Err(RelayError::Simulation(err.to_string()));
+        }
+
+        for call in &calls {
+            self.rate_limiter.acquire(call.attentee, call.event)?;
+        }
+
+        let recent_blockhash = transaction.message.recent_blockhash;
+        transaction
+            .try_partial_sign(&[&self.config.fee_payer], recent_blockhash)
+            .map_err(|_| RelayError::Signature(self.fee_payer()))?;
+
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    /// Only events created by an allowed organizer are sponsored.
+    async fn check_event(&self, event: &Pubkey) -> Result<(), RelayError> {
+        let account = self
+            .rpc
+            .get_account(event)
+            .await
+            .map_err(|_| RelayError::EventNotSponsored(*event))?;
+
+        if account.owner != counter::ID {
+            return Err(RelayError::EventNotSponsored(*event));
+        }
+
+        let event_account = counter::Event::try_deserialize(&mut account.data.as_slice())
+            .map_err(|_| RelayError::EventNotSponsored(*event))?;
+
+        if !self
+            .config
+            .allowed_creators
+            .contains(&event_account.creator)
+        {
+            return Err(RelayError::EventNotSponsored(*event));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use relayer::{config::Config, server, Relayer};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_env()?;
+    let bind_addr = config.bind_addr.clone();
+    let relayer = Arc::new(Relayer::new(config));
+
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+    tracing::info!(%bind_addr, fee_payer = %relayer.fee_payer(), "relayer listening");
+
+    axum::serve(listener, server::router(relayer)).await?;
+    Ok(())
+}
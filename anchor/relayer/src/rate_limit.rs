@@ -0,0 +1,92 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::RelayError;
+
+/// Sliding window limiter on sponsored calls per wallet, per wallet/event and
+/// per event. Wallets cost nothing to create, so the per-event budget is what
+/// caps the rent and fees a single event can draw from the relayer.
+pub struct RateLimiter {
+    window: Duration,
+    max_per_wallet: usize,
+    max_per_wallet_event: usize,
+    max_per_event: usize,
+    calls: Mutex<Calls>,
+}
+
+#[derive(Default)]
+struct Calls {
+    by_wallet: HashMap<Pubkey, VecDeque<(Instant, Pubkey)>>,
+    by_event: HashMap<Pubkey, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        window: Duration,
+        max_per_wallet: u32,
+        max_per_wallet_event: u32,
+        max_per_event: u32,
+    ) -> Self {
+        Self {
+            window,
+            max_per_wallet: max_per_wallet as usize,
+            max_per_wallet_event: max_per_wallet_event as usize,
+            max_per_event: max_per_event as usize,
+            calls: Mutex::new(Calls::default()),
+        }
+    }
+
+    /// Records a sponsored call, failing if the wallet or the event is over
+    /// its limit.
+    pub fn acquire(&self, wallet: Pubkey, event: Pubkey) -> Result<(), RelayError> {
+        let now = Instant::now();
+        let mut calls = self.calls.lock().unwrap();
+
+        // calls that left the window are dropped, and with them every wallet
+        // and event that has none left
+        calls.by_wallet.retain(|_, wallet_calls| {
+            wallet_calls.retain(|(at, _)| now.duration_since(*at) < self.window);
+            !wallet_calls.is_empty()
+        });
+        calls.by_event.retain(|_, event_calls| {
+            event_calls.retain(|at| now.duration_since(*at) < self.window);
+            !event_calls.is_empty()
+        });
+
+        if calls.by_event.get(&event).map_or(0, VecDeque::len) >= self.max_per_event {
+            return Err(RelayError::EventBudgetExhausted(event));
+        }
+
+        let wallet_calls = calls.by_wallet.get(&wallet);
+        let event_calls = wallet_calls.map_or(0, |wallet_calls| {
+            wallet_calls
+                .iter()
+                .filter(|(_, called_event)| *called_event == event)
+                .count()
+        });
+
+        if wallet_calls.map_or(0, VecDeque::len) >= self.max_per_wallet
+            || event_calls >= self.max_per_wallet_event
+        {
+            return Err(RelayError::RateLimited(wallet));
+        }
+
+        calls
+            .by_wallet
+            .entry(wallet)
+            .or_default()
+            .push_back((now, event));
+        calls.by_event.entry(event).or_default().push_back(now);
+        Ok(())
+    }
+
+    /// Wallets with sponsored calls in the current window.
+    pub fn tracked_wallets(&self) -> usize {
+        self.calls.lock().unwrap().by_wallet.len()
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::RelayError, Relayer};
+
+#[derive(Deserialize)]
+pub struct RelayRequest {
+    /// Base64 encoded, bincode serialized transaction signed by the attentee.
+    pub transaction: String,
+}
+
+#[derive(Serialize)]
+pub struct RelayResponse {
+    pub signature: String,
+}
+
+pub fn router(relayer: Arc<Relayer>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/relay", post(relay))
+        .with_state(relayer)
+}
+
+async fn health(State(relayer): State<Arc<Relayer>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "fee_payer": relayer.fee_payer().to_string() }))
+}
+
+async fn relay(
+    State(relayer): State<Arc<Relayer>>,
+    Json(request): Json<RelayRequest>,
+) -> Result<Json<RelayResponse>, RelayError> {
+    let signature = relayer
+        .relay(&request.transaction)
+        .await
+        .inspect_err(|err| {
+            tracing::warn!(%err, "rejected relay request");
+        })?;
+
+    tracing::info!(%signature, "relayed transaction");
+    Ok(Json(RelayResponse {
+        signature: signature.to_string(),
+    }))
+}
@@ -0,0 +1,224 @@
+use anchor_lang::Discriminator;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bincode::Options;
+use solana_compute_budget_interface as compute_budget;
+use solana_sdk::{
+    packet::PACKET_DATA_SIZE, pubkey::Pubkey, sanitize::Sanitize, transaction::Transaction,
+};
+
+use crate::error::RelayError;
+
+/// Upper bound on instructions in a sponsored transaction.
+pub const MAX_INSTRUCTIONS: usize = 4;
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit` tag. Priority fees would be
+/// paid by the relayer, so no other compute budget instruction is accepted.
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+/// Positions of the checked accounts, following the field order of the
+/// program's `RegisterEvent`, `CheckIn`, `MintNft` and `WalkInMint` account
+/// structs.
+pub mod account_index {
+    pub const ATTENTEE: usize = 0;
+    pub const PAYER: usize = 1;
+    pub const EVENT: usize = 2;
+    pub const REGISTER_EVENT_REGISTRATION: usize = 6;
+    pub const MINT_NFT_REGISTRATION: usize = 3;
+    pub const WALK_IN_MINT_REGISTRATION: usize = 3;
+    pub const CHECK_IN_EVENT: usize = 1;
+    pub const CHECK_IN_REGISTRATION: usize = 2;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SponsoredInstruction {
+    RegisterEvent,
+    CheckIn,
+    MintNft,
+    WalkInMint,
+}
+
+impl SponsoredInstruction {
+    fn from_data(data: &[u8]) -> Option<Self> {
+        if data.starts_with(counter::instruction::RegisterEvent::DISCRIMINATOR) {
+            Some(Self::RegisterEvent)
+        } else if data.starts_with(counter::instruction::CheckIn::DISCRIMINATOR) {
+            Some(Self::CheckIn)
+        } else if data.starts_with(counter::instruction::MintNft::DISCRIMINATOR) {
+            Some(Self::MintNft)
+        } else if data.starts_with(counter::instruction::WalkInMint::DISCRIMINATOR) {
+            Some(Self::WalkInMint)
+        } else {
+            None
+        }
+    }
+
+    /// Positions of the payer, event and registration accounts. `check_in`
+    /// pays no rent, so it has no payer.
+    fn layout(self) -> (Option<usize>, usize, usize) {
+        match self {
+            Self::RegisterEvent => (
+                Some(account_index::PAYER),
+                account_index::EVENT,
+                account_index::REGISTER_EVENT_REGISTRATION,
+            ),
+            Self::CheckIn => (
+                None,
+                account_index::CHECK_IN_EVENT,
+                account_index::CHECK_IN_REGISTRATION,
+            ),
+            Self::MintNft => (
+                Some(account_index::PAYER),
+                account_index::EVENT,
+                account_index::MINT_NFT_REGISTRATION,
+            ),
+            Self::WalkInMint => (
+                Some(account_index::PAYER),
+                account_index::EVENT,
+                account_index::WALK_IN_MINT_REGISTRATION,
+            ),
+        }
+    }
+}
+
+/// A program call the relayer agreed to pay for.
+#[derive(Debug)]
+pub struct SponsoredCall {
+    pub instruction: SponsoredInstruction,
+    pub attentee: Pubkey,
+    pub event: Pubkey,
+}
+
+/// Decodes a transaction, rejecting out of range account indices up front.
+/// Anything larger than a packet could never land, so it is refused before
+/// decoding, the same way the validator's RPC does.
+pub fn decode_transaction(encoded: &str) -> Result<Transaction, RelayError> {
+    if encoded.len() > base64::encoded_len(PACKET_DATA_SIZE, true).unwrap_or(usize::MAX) {
+        return Err(RelayError::Decode);
+    }
+
+    let bytes = STANDARD.decode(encoded).map_err(|_| RelayError::Decode)?;
+    if bytes.len() > PACKET_DATA_SIZE {
+        return Err(RelayError::Decode);
+    }
+
+    let transaction: Transaction = bincode::DefaultOptions::new()
+        .with_limit(PACKET_DATA_SIZE as u64)
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize(&bytes)
+        .map_err(|_| RelayError::Decode)?;
+
+    transaction.sanitize().map_err(|_| RelayError::Decode)?;
+    Ok(transaction)
+}
+
+/// Checks that a transaction only spends the relayer's lamports on
+/// `register_event` / `check_in` / `mint_nft` / `walk_in_mint` for an attentee
+/// that already signed it.
+/// Expects a transaction that went through [`decode_transaction`].
+pub fn validate_transaction(
+    transaction: &Transaction,
+    fee_payer: &Pubkey,
+) -> Result<Vec<SponsoredCall>, RelayError> {
+    let message = &transaction.message;
+    let keys = &message.account_keys;
+
+    if keys.first() != Some(fee_payer) {
+        return Err(RelayError::FeePayer);
+    }
+
+    if message.instructions.is_empty() || message.instructions.len() > MAX_INSTRUCTIONS {
+        return Err(RelayError::Instruction(0));
+    }
+
+    // every signature except the relayer's must already be present
+    let message_data = message.serialize();
+    let required_signatures = message.header.num_required_signatures as usize;
+
+    for (index, key) in keys.iter().enumerate().take(required_signatures).skip(1) {
+        let signed = transaction
+            .signatures
+            .get(index)
+            .is_some_and(|signature| signature.verify(key.as_ref(), &message_data));
+
+        if !signed {
+            return Err(RelayError::Signature(*key));
+        }
+    }
+
+    let mut calls = Vec::new();
+
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        let program_id = instruction.program_id(keys);
+
+        if *program_id == compute_budget::id() {
+            if instruction.data.first() != Some(&SET_COMPUTE_UNIT_LIMIT) {
+                return Err(RelayError::Instruction(index));
+            }
+            continue;
+        }
+
+        if *program_id != counter::ID {
+            return Err(RelayError::Instruction(index));
+        }
+
+        let sponsored = SponsoredInstruction::from_data(&instruction.data)
+            .ok_or(RelayError::Instruction(index))?;
+        let (payer_index, event_index, registration_index) = sponsored.layout();
+
+        let key_index = |position: usize| {
+            instruction
+                .accounts
+                .get(position)
+                .map(|key_index| *key_index as usize)
+                .ok_or(RelayError::Accounts(index))
+        };
+
+        let attentee_index = key_index(account_index::ATTENTEE)?;
+        let attentee = keys[attentee_index];
+        let event = keys[key_index(event_index)?];
+        let registration = keys[key_index(registration_index)?];
+        let payer = payer_index
+            .map(|position| key_index(position).map(|key_index| keys[key_index]))
+            .transpose()?;
+
+        if payer.is_some_and(|payer| payer != *fee_payer)
+            || attentee == *fee_payer
+            || !message.is_signer(attentee_index)
+        {
+            return Err(RelayError::Accounts(index));
+        }
+
+        // the relayer signs the whole transaction, so its key must not show
+        // up anywhere it could act as an authority
+        let relayer_elsewhere =
+            instruction
+                .accounts
+                .iter()
+                .enumerate()
+                .any(|(position, key_index)| {
+                    Some(position) != payer_index && keys[*key_index as usize] == *fee_payer
+                });
+
+        let (expected_registration, _) = Pubkey::find_program_address(
+            &[b"attentee", event.as_ref(), attentee.as_ref()],
+            &counter::ID,
+        );
+
+        if relayer_elsewhere || registration != expected_registration {
+            return Err(RelayError::Accounts(index));
+        }
+
+        calls.push(SponsoredCall {
+            instruction: sponsored,
+            attentee,
+            event,
+        });
+    }
+
+    if calls.is_empty() {
+        return Err(RelayError::Instruction(0));
+    }
+
+    Ok(calls)
+}
@@ -0,0 +1,369 @@
+use std::{env, str::FromStr, time::Duration};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use relayer::{
+    config::Config,
+    error::RelayError,
+    rate_limit::RateLimiter,
+    validate::{decode_transaction, validate_transaction, SponsoredInstruction},
+    Relayer,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &counter::ID).0
+}
+
+fn register_event(attentee: &Pubkey, payer: &Pubkey, creator: &Pubkey, name: &str) -> Instruction {
+    let event_account = pda(&[b"event", creator.as_ref(), name.as_bytes()]);
+
+    Instruction {
+        program_id: counter::ID,
+        accounts: counter::accounts::RegisterEvent {
+            attentee: *attentee,
+            payer: *payer,
+            event_account,
+            creator: *creator,
+            vault: pda(&[b"vault", event_account.as_ref()]),
+            tier_account: pda(&[b"tier", event_account.as_ref(), &[0]]),
+            registration_account: pda(&[b"attentee", event_account.as_ref(), attentee.as_ref()]),
+            questions_account: None,
+            waitlist_entry: pda(&[b"waitlist", event_account.as_ref(), attentee.as_ref()]),
+            waitlist_payer: None,
+            config: pda(&[b"config"]),
+            treasury: Pubkey::new_unique(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: counter::instruction::RegisterEvent {
+            tier_id: 0,
+            answers: vec![],
+        }
+        .data(),
+    }
+}
+
+fn check_in(attentee: &Pubkey, creator: &Pubkey, name: &str) -> Instruction {
+    let event_account = pda(&[b"event", creator.as_ref(), name.as_bytes()]);
+
+    Instruction {
+        program_id: counter::ID,
+        accounts: counter::accounts::CheckIn {
+            attentee: *attentee,
+            event_account,
+            registration_account: pda(&[b"attentee", event_account.as_ref(), attentee.as_ref()]),
+        }
+        .to_account_metas(None),
+        data: counter::instruction::CheckIn {
+            attentance_code: [7; 32],
+        }
+        .data(),
+    }
+}
+
+fn sign(
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+    signers: &[&Keypair],
+    blockhash: Hash,
+) -> Transaction {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(fee_payer));
+    transaction.partial_sign(signers, blockhash);
+    transaction
+}
+
+#[test]
+fn accepts_attentee_signed_registration() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = sign(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            register_event(&attentee.pubkey(), &relayer.pubkey(), &creator, "meetup"),
+        ],
+        &relayer.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+
+    let calls = validate_transaction(&transaction, &relayer.pubkey()).unwrap();
+
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].instruction, SponsoredInstruction::RegisterEvent);
+    assert_eq!(calls[0].attentee, attentee.pubkey());
+}
+
+#[test]
+fn accepts_check_in_without_payer_account() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = sign(
+        &[check_in(&attentee.pubkey(), &creator, "meetup")],
+        &relayer.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+
+    let calls = validate_transaction(&transaction, &relayer.pubkey()).unwrap();
+
+    assert_eq!(calls[0].instruction, SponsoredInstruction::CheckIn);
+    assert_eq!(
+        calls[0].event,
+        pda(&[b"event", creator.as_ref(), b"meetup"])
+    );
+}
+
+#[test]
+fn rejects_transactions_larger_than_a_packet() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = sign(
+        &[check_in(&attentee.pubkey(), &creator, "meetup")],
+        &relayer.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+    let mut bytes = bincode::serialize(&transaction).unwrap();
+    assert!(decode_transaction(&STANDARD.encode(&bytes)).is_ok());
+
+    bytes.resize(PACKET_DATA_SIZE + 1, 0);
+    assert!(matches!(
+        decode_transaction(&STANDARD.encode(&bytes)),
+        Err(RelayError::Decode)
+    ));
+}
+
+#[test]
+fn rejects_other_fee_payers() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = sign(
+        &[register_event(
+            &attentee.pubkey(),
+            &relayer.pubkey(),
+            &creator,
+            "meetup",
+        )],
+        &attentee.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+
+    assert!(matches!(
+        validate_transaction(&transaction, &relayer.pubkey()),
+        Err(RelayError::FeePayer)
+    ));
+}
+
+#[test]
+fn rejects_missing_attentee_signature() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = Transaction::new_with_payer(
+        &[register_event(
+            &attentee.pubkey(),
+            &relayer.pubkey(),
+            &creator,
+            "meetup",
+        )],
+        Some(&relayer.pubkey()),
+    );
+
+    assert!(matches!(
+        validate_transaction(&transaction, &relayer.pubkey()),
+        Err(RelayError::Signature(key)) if key == attentee.pubkey()
+    ));
+}
+
+#[test]
+fn rejects_other_programs_and_priority_fees() {
+    let relayer = Keypair::new();
+    let attentee = Keypair::new();
+    let creator = Pubkey::new_unique();
+    let register = register_event(&attentee.pubkey(), &relayer.pubkey(), &creator, "meetup");
+
+    let drain = sign(
+        &[
+            register.clone(),
+            system_instruction::transfer(&relayer.pubkey(), &attentee.pubkey(), LAMPORTS_PER_SOL),
+        ],
+        &relayer.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+
+    let priority_fee = sign(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+            register,
+        ],
+        &relayer.pubkey(),
+        &[&attentee],
+        Hash::new_unique(),
+    );
+
+    assert!(matches!(
+        validate_transaction(&drain, &relayer.pubkey()),
+        Err(RelayError::Instruction(1))
+    ));
+    assert!(matches!(
+        validate_transaction(&priority_fee, &relayer.pubkey()),
+        Err(RelayError::Instruction(0))
+    ));
+}
+
+#[test]
+fn rejects_relayer_as_attentee() {
+    let relayer = Keypair::new();
+    let creator = Pubkey::new_unique();
+
+    let transaction = sign(
+        &[register_event(
+            &relayer.pubkey(),
+            &relayer.pubkey(),
+            &creator,
+            "meetup",
+        )],
+        &relayer.pubkey(),
+        &[],
+        Hash::new_unique(),
+    );
+
+    assert!(matches!(
+        validate_transaction(&transaction, &relayer.pubkey()),
+        Err(RelayError::Accounts(0))
+    ));
+}
+
+#[test]
+fn limits_calls_per_wallet_and_event() {
+    let limiter = RateLimiter::new(Duration::from_secs(60), 3, 2, 100);
+    let wallet = Pubkey::new_unique();
+    let event = Pubkey::new_unique();
+
+    limiter.acquire(wallet, event).unwrap();
+    limiter.acquire(wallet, event).unwrap();
+    assert!(matches!(
+        limiter.acquire(wallet, event),
+        Err(RelayError::RateLimited(_))
+    ));
+
+    limiter.acquire(wallet, Pubkey::new_unique()).unwrap();
+    assert!(matches!(
+        limiter.acquire(wallet, Pubkey::new_unique()),
+        Err(RelayError::RateLimited(_))
+    ));
+}
+
+#[test]
+fn limits_calls_per_event_across_wallets() {
+    let limiter = RateLimiter::new(Duration::from_secs(60), 3, 2, 2);
+    let event = Pubkey::new_unique();
+
+    limiter.acquire(Pubkey::new_unique(), event).unwrap();
+    limiter.acquire(Pubkey::new_unique(), event).unwrap();
+    assert!(matches!(
+        limiter.acquire(Pubkey::new_unique(), event),
+        Err(RelayError::EventBudgetExhausted(_))
+    ));
+
+    limiter
+        .acquire(Pubkey::new_unique(), Pubkey::new_unique())
+        .unwrap();
+}
+
+#[test]
+fn forgets_wallets_once_their_calls_leave_the_window() {
+    let limiter = RateLimiter::new(Duration::from_millis(20), 3, 2, 100);
+
+    limiter
+        .acquire(Pubkey::new_unique(), Pubkey::new_unique())
+        .unwrap();
+    limiter
+        .acquire(Pubkey::new_unique(), Pubkey::new_unique())
+        .unwrap();
+    assert_eq!(limiter.tracked_wallets(), 2);
+
+    std::thread::sleep(Duration::from_millis(30));
+    limiter
+        .acquire(Pubkey::new_unique(), Pubkey::new_unique())
+        .unwrap();
+    assert_eq!(limiter.tracked_wallets(), 1);
+}
+
+/// Needs `solana-test-validator` running with the program deployed and an
+/// event with a free tier 0, named by `RELAYER_TEST_CREATOR`/`RELAYER_TEST_EVENT`.
+#[tokio::test]
+#[ignore = "needs a local validator with a sponsored event"]
+async fn relays_registration_on_local_validator() {
+    let creator = Pubkey::from_str(&env::var("RELAYER_TEST_CREATOR").unwrap()).unwrap();
+    let name = env::var("RELAYER_TEST_EVENT").unwrap();
+    let rpc =
+        RpcClient::new_with_commitment("http://127.0.0.1:8899", CommitmentConfig::confirmed());
+
+    let fee_payer = Keypair::new();
+    let signature = rpc
+        .request_airdrop(&fee_payer.pubkey(), LAMPORTS_PER_SOL)
+        .unwrap();
+    rpc.poll_for_signature(&signature).unwrap();
+
+    let attentee = Keypair::new();
+    let transaction = sign(
+        &[register_event(
+            &attentee.pubkey(),
+            &fee_payer.pubkey(),
+            &creator,
+            &name,
+        )],
+        &fee_payer.pubkey(),
+        &[&attentee],
+        rpc.get_latest_blockhash().unwrap(),
+    );
+
+    let relayer = Relayer::new(Config {
+        rpc_url: "http://127.0.0.1:8899".to_string(),
+        bind_addr: "127.0.0.1:0".to_string(),
+        fee_payer,
+        allowed_creators: vec![creator],
+        rate_limit_window: Duration::from_secs(60),
+        max_per_wallet: 10,
+        max_per_wallet_event: 2,
+        max_per_event: 500,
+    });
+
+    relayer
+        .relay(&STANDARD.encode(bincode::serialize(&transaction).unwrap()))
+        .await
+        .unwrap();
+
+    let event_account = pda(&[b"event", creator.as_ref(), name.as_bytes()]);
+    let registration = pda(&[
+        b"attentee",
+        event_account.as_ref(),
+        attentee.pubkey().as_ref(),
+    ]);
+    assert!(rpc.get_account(&registration).is_ok());
+}
@@ -0,0 +1,1494 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+import { getMetadataAccountDataSerializer } from '@metaplex-foundation/mpl-token-metadata'
+import {
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token'
+import { createHash } from 'crypto'
+import { ComputeBudgetProgram, Ed25519Program, Keypair, LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js'
+import { Counter } from '../target/types/counter'
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s')
+const BPF_UPGRADEABLE_LOADER_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+
+describe('collection', () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+  const payer = provider.wallet as anchor.Wallet
+
+  const program = anchor.workspace.Counter as Program<Counter>
+
+  const name = `collection-${Date.now() % 1_000_000}`
+  const attentanceCode = Array(32).fill(7)
+  const now = Math.floor(Date.now() / 1000)
+  const tierId = 0
+  const paidTierId = 1
+  const feeBasisPoints = 250
+  const treasury = Keypair.generate().publicKey
+
+  const pda = (seeds: Buffer[], programId = program.programId) => PublicKey.findProgramAddressSync(seeds, programId)[0]
+  const metadataPda = (mint: PublicKey) =>
+    pda([Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()], TOKEN_METADATA_PROGRAM_ID)
+  const masterEditionPda = (mint: PublicKey) =>
+    pda(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+      TOKEN_METADATA_PROGRAM_ID,
+    )
+
+  const tierPda = (eventAccount: PublicKey, id: number) =>
+    pda([Buffer.from('tier'), eventAccount.toBuffer(), Buffer.from([id])])
+
+  // every test that moves an event through time gets an event of its own,
+  // occurrences of a series share the series' collection
+  const eventKeys = (
+    eventName: string,
+    collectionMint = pda([Buffer.from('collection_mint'), Buffer.from(eventName)]),
+  ) => {
+    const eventAccount = pda([Buffer.from('event'), payer.publicKey.toBuffer(), Buffer.from(eventName)])
+
+    return {
+      name: eventName,
+      eventAccount,
+      collectionMint,
+      tierAccount: tierPda(eventAccount, tierId),
+      vault: pda([Buffer.from('vault'), eventAccount.toBuffer()]),
+      collectionAuthorityRecord: pda(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          collectionMint.toBuffer(),
+          Buffer.from('collection_authority'),
+          collectionMint.toBuffer(),
+        ],
+        TOKEN_METADATA_PROGRAM_ID,
+      ),
+    }
+  }
+  type EventKeys = ReturnType<typeof eventKeys>
+
+  const main = eventKeys(name)
+  const { eventAccount, collectionMint, tierAccount, vault, collectionAuthorityRecord } = main
+  const config = pda([Buffer.from('config')])
+  const claimCodesAccount = pda([Buffer.from('claim_codes'), eventAccount.toBuffer()])
+
+  const unixNow = () => Math.floor(Date.now() / 1000)
+  const sleep = (seconds: number) => new Promise((resolve) => setTimeout(resolve, seconds * 1000))
+
+  async function fund(wallet: PublicKey, lamports = LAMPORTS_PER_SOL) {
+    const signature = await provider.connection.requestAirdrop(wallet, lamports)
+    await provider.connection.confirmTransaction(signature)
+  }
+
+  async function collectionSize(event: EventKeys = main): Promise<number> {
+    const info = await provider.connection.getAccountInfo(metadataPda(event.collectionMint))
+    const [metadata] = getMetadataAccountDataSerializer().deserialize(info!.data)
+
+    if (metadata.collectionDetails.__option !== 'Some' || metadata.collectionDetails.value.__kind !== 'V1') {
+      throw new Error('collection is not sized')
+    }
+    return Number(metadata.collectionDetails.value.size)
+  }
+
+  const registrationPda = (attentee: Keypair, event: EventKeys = main) =>
+    pda([Buffer.from('attentee'), event.eventAccount.toBuffer(), attentee.publicKey.toBuffer()])
+
+  const waitlistPda = (attentee: Keypair, event: EventKeys = main) =>
+    pda([Buffer.from('waitlist'), event.eventAccount.toBuffer(), attentee.publicKey.toBuffer()])
+
+  const badgeAccounts = (attentee: Keypair, event: EventKeys = main) => {
+    const registrationAccount = registrationPda(attentee, event)
+    const nftMint = pda([Buffer.from('nft_mint'), registrationAccount.toBuffer()])
+
+    return {
+      attentee: attentee.publicKey,
+      eventAccount: event.eventAccount,
+      registrationAccount,
+      tierAccount: event.tierAccount,
+      collectionMint: event.collectionMint,
+      nftMint,
+      childNftMetadata: metadataPda(nftMint),
+      childNftMasterEdition: masterEditionPda(nftMint),
+      metadata: metadataPda(event.collectionMint),
+      masterEdition: masterEditionPda(event.collectionMint),
+      collectionAuthorityRecord: event.collectionAuthorityRecord,
+      destination: getAssociatedTokenAddressSync(nftMint, attentee.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }
+  }
+
+  // the organizer wallet sponsors rent and fees, attentees hold no SOL
+  const register = (
+    attentee: Keypair,
+    event: EventKeys = main,
+    tier = tierId,
+    waitlistPayer: PublicKey | null = null,
+  ) =>
+    program.methods
+      .registerEvent(tier, [])
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        payer: payer.publicKey,
+        eventAccount: event.eventAccount,
+        creator: payer.publicKey,
+        vault: event.vault,
+        tierAccount: tierPda(event.eventAccount, tier),
+        registrationAccount: registrationPda(attentee, event),
+        questionsAccount: null,
+        // registering takes the attentee off the waitlist, the rent goes back to whoever paid it
+        waitlistEntry: waitlistPda(attentee, event),
+        waitlistPayer,
+        treasury,
+      })
+      .signers([attentee])
+      .rpc()
+
+  const checkIn = (attentee: Keypair, event: EventKeys = main, code = attentanceCode) =>
+    program.methods
+      .checkIn(code)
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        eventAccount: event.eventAccount,
+        registrationAccount: registrationPda(attentee, event),
+      })
+      .signers([attentee])
+      .rpc()
+
+  type ClaimCode = { index: number; code: number[]; proof: number[][] }
+
+  const mintBadge = (attentee: Keypair, claimCode: ClaimCode | null = null, event: EventKeys = main) =>
+    program.methods
+      .mintNft(claimCode)
+      .accountsPartial({
+        ...badgeAccounts(attentee, event),
+        payer: payer.publicKey,
+        claimCodesAccount: claimCode ? pda([Buffer.from('claim_codes'), event.eventAccount.toBuffer()]) : null,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([attentee])
+      .rpc()
+
+  const airdropBadge = (attentee: Keypair, event: EventKeys = main) =>
+    program.methods
+      .airdropBadge()
+      .accountsPartial({ ...badgeAccounts(attentee, event), creator: payer.publicKey })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc()
+
+  // each badge takes the attentee, registration, mint, metadata, master edition and token account
+  const airdropBadges = (attentees: Keypair[], event: EventKeys = main) =>
+    program.methods
+      .airdropBadges()
+      .accountsPartial({
+        creator: payer.publicKey,
+        eventAccount: event.eventAccount,
+        tierAccount: event.tierAccount,
+        collectionMint: event.collectionMint,
+        metadata: metadataPda(event.collectionMint),
+        masterEdition: masterEditionPda(event.collectionMint),
+        collectionAuthorityRecord: event.collectionAuthorityRecord,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        attentees.flatMap((attentee) => {
+          const accounts = badgeAccounts(attentee, event)
+          return [
+            { pubkey: attentee.publicKey, isSigner: false, isWritable: false },
+            { pubkey: accounts.registrationAccount, isSigner: false, isWritable: true },
+            { pubkey: accounts.nftMint, isSigner: false, isWritable: true },
+            { pubkey: accounts.childNftMetadata, isSigner: false, isWritable: true },
+            { pubkey: accounts.childNftMasterEdition, isSigner: false, isWritable: true },
+            { pubkey: accounts.destination, isSigner: false, isWritable: true },
+          ]
+        }),
+      )
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 })])
+      .rpc()
+
+  const joinWaitlist = (attentee: Keypair, event: EventKeys = main, sponsor?: Keypair) =>
+    program.methods
+      .joinWaitlist()
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        payer: sponsor?.publicKey ?? payer.publicKey,
+        eventAccount: event.eventAccount,
+        waitlistEntry: waitlistPda(attentee, event),
+        registrationAccount: registrationPda(attentee, event),
+      })
+      .signers(sponsor ? [attentee, sponsor] : [attentee])
+      .rpc()
+
+  const walkInMint = (walkIn: Keypair, event: EventKeys = main) =>
+    program.methods
+      .walkInMint(tierId, attentanceCode)
+      .accountsPartial({ ...badgeAccounts(walkIn, event), payer: payer.publicKey })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([walkIn])
+      .rpc()
+
+  type TierSettings = { price?: number; capacity?: number; start?: number; end?: number; uri?: string }
+
+  const createTier = (event: EventKeys, id: number, tierName: string, settings: TierSettings = {}) =>
+    program.methods
+      .createTicketTier(
+        id,
+        tierName,
+        new anchor.BN(settings.price ?? 0),
+        new anchor.BN(settings.capacity ?? 10),
+        new anchor.BN(settings.start ?? unixNow() - 60),
+        new anchor.BN(settings.end ?? unixNow() + 3600),
+        settings.uri ?? `https://example.com/${id}.json`,
+      )
+      .accountsPartial({
+        creator: payer.publicKey,
+        eventAccount: event.eventAccount,
+        tierAccount: tierPda(event.eventAccount, id),
+      })
+      .rpc()
+
+  // a free tier 0 with room for ten, open for the next hour
+  async function createEvent(suffix: string, start: number, end: number, capacity = 10): Promise<EventKeys> {
+    const event = eventKeys(`${name}-${suffix}`)
+
+    await program.methods
+      .initializeEvent(
+        event.name,
+        suffix,
+        'https://example.com/event.json',
+        attentanceCode,
+        new anchor.BN(start),
+        new anchor.BN(end),
+        new anchor.BN(capacity),
+        event.collectionMint,
+        'TLT',
+        0,
+        [{ address: payer.publicKey, share: 100 }],
+      )
+      .accountsPartial({
+        payer: payer.publicKey,
+        eventAccount: event.eventAccount,
+        collectionMint: event.collectionMint,
+        collectionTokenAccount: getAssociatedTokenAddressSync(event.collectionMint, payer.publicKey),
+        metadata: metadataPda(event.collectionMint),
+        masterEdition: masterEditionPda(event.collectionMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc()
+
+    await createTier(event, tierId, 'General')
+    return event
+  }
+
+  const organizerAccount = pda([Buffer.from('organizer'), payer.publicKey.toBuffer()])
+  const CLEANUP_GRACE_PERIOD = 7 * 24 * 60 * 60
+  const CLEANUP_BOUNTY_LAMPORTS = 100_000
+
+  const closeEvent = (event: EventKeys) =>
+    program.methods
+      .closeEvent()
+      .accountsPartial({
+        payer: payer.publicKey,
+        eventAccount: event.eventAccount,
+        organizerAccount,
+        vault: event.vault,
+      })
+      .rpc()
+
+  // the organizer wallet pays the fees, so the cranker's balance only moves by the bounty
+  const closeStaleRegistration = (cranker: Keypair, attentee: Keypair, event: EventKeys) =>
+    program.methods
+      .closeStaleRegistration()
+      .accountsPartial({
+        cranker: cranker.publicKey,
+        eventAccount: event.eventAccount,
+        registrationAccount: registrationPda(attentee, event),
+        payer: payer.publicKey,
+        attentee: attentee.publicKey,
+        vault: event.vault,
+      })
+      .signers([cranker])
+      .rpc()
+
+  const closeExpiredEvent = (cranker: Keypair, event: EventKeys) =>
+    program.methods
+      .closeExpiredEvent()
+      .accountsPartial({
+        cranker: cranker.publicKey,
+        eventAccount: event.eventAccount,
+        creator: payer.publicKey,
+        organizerAccount,
+        vault: event.vault,
+        config,
+      })
+      .signers([cranker])
+      .rpc()
+
+  const cancelRegistration = (attentee: Keypair, event: EventKeys = main) =>
+    program.methods
+      .cancelRegistration()
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        eventAccount: event.eventAccount,
+        registrationAccount: registrationPda(attentee, event),
+        payer: payer.publicKey,
+        tierAccount: event.tierAccount,
+        vault: event.vault,
+      })
+      .signers([attentee])
+      .rpc()
+
+  // moves an event through time, the validator clock cannot be warped
+  const editEvent = (event: EventKeys, start: number, end: number, capacity = 10) =>
+    program.methods
+      .editEvent(event.name, attentanceCode, new anchor.BN(start), new anchor.BN(end), new anchor.BN(capacity))
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+
+  const sessionPda = (event: EventKeys, id: number) =>
+    pda([Buffer.from('session'), event.eventAccount.toBuffer(), Buffer.from([id])])
+
+  const createSession = (event: EventKeys, id: number, code: number[]) =>
+    program.methods
+      .createSession(id, new anchor.BN(unixNow() - 60), new anchor.BN(unixNow() + 3600), code)
+      .accountsPartial({
+        creator: payer.publicKey,
+        eventAccount: event.eventAccount,
+        sessionAccount: sessionPda(event, id),
+      })
+      .rpc()
+
+  const checkInSession = (attentee: Keypair, event: EventKeys, id: number, code: number[]) =>
+    program.methods
+      .checkInSession(id, code)
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        eventAccount: event.eventAccount,
+        sessionAccount: sessionPda(event, id),
+        registrationAccount: registrationPda(attentee, event),
+      })
+      .signers([attentee])
+      .rpc()
+
+  const claimForfeitedDeposits = () =>
+    program.methods
+      .claimForfeitedDeposits()
+      .accountsPartial({ creator: payer.publicKey, eventAccount, beneficiary: payer.publicKey, vault })
+      .rpc()
+
+  // ceilings per instruction, raise them deliberately when a change needs more
+  const COMPUTE_UNIT_CEILINGS = { registerEvent: 40_000, checkIn: 20_000, mintNft: 160_000 }
+
+  async function computeUnits(signature: string): Promise<number> {
+    await provider.connection.confirmTransaction(signature, 'confirmed')
+    const transaction = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    })
+    return transaction!.meta!.computeUnitsConsumed!
+  }
+
+  async function registerAndMint(attentee: Keypair) {
+    await register(attentee)
+    await checkIn(attentee)
+    await mintBadge(attentee)
+  }
+
+  it('Initialize Config', async () => {
+    await program.methods
+      .initializeConfig(feeBasisPoints, treasury, new anchor.BN(1_000))
+      .accountsPartial({
+        admin: payer.publicKey,
+        config,
+        programData: pda([program.programId.toBuffer()], BPF_UPGRADEABLE_LOADER_ID),
+      })
+      .rpc()
+
+    const account = await program.account.config.fetch(config)
+    expect(account.admin.toBase58()).toEqual(payer.publicKey.toBase58())
+    expect(account.feeBasisPoints).toEqual(feeBasisPoints)
+    expect(account.paused).toEqual(false)
+  })
+
+  it('Initialize Event', async () => {
+    await program.methods
+      .initializeEvent(
+        name,
+        'collection size test',
+        'https://example.com/event.json',
+        attentanceCode,
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 3600),
+        new anchor.BN(20),
+        collectionMint,
+        'TLT',
+        500,
+        [{ address: payer.publicKey, share: 100 }],
+      )
+      .accountsPartial({
+        payer: payer.publicKey,
+        eventAccount,
+        collectionMint,
+        collectionTokenAccount: getAssociatedTokenAddressSync(collectionMint, payer.publicKey),
+        metadata: metadataPda(collectionMint),
+        masterEdition: masterEditionPda(collectionMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc()
+
+    await program.methods
+      .createTicketTier(
+        tierId,
+        'General',
+        new anchor.BN(0),
+        new anchor.BN(10),
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 3600),
+        'https://example.com/general.json',
+      )
+      .accountsPartial({ creator: payer.publicKey, eventAccount, tierAccount })
+      .rpc()
+
+    // no deposit, the beneficiary only lets the pause tests reach the payout
+    await program.methods
+      .setEventDeposit(name, new anchor.BN(0), payer.publicKey, new anchor.BN(now + 7200))
+      .accountsPartial({ creator: payer.publicKey, eventAccount })
+      .rpc()
+
+    const collectionToken = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(collectionMint, payer.publicKey),
+    )
+
+    expect(collectionToken.owner.toBase58()).toEqual(payer.publicKey.toBase58())
+    expect(Number(collectionToken.amount)).toEqual(1)
+    expect(await collectionSize()).toEqual(0)
+
+    const info = await provider.connection.getAccountInfo(metadataPda(collectionMint))
+    const [metadata] = getMetadataAccountDataSerializer().deserialize(info!.data)
+    expect(metadata.sellerFeeBasisPoints).toEqual(500)
+    expect(metadata.creators.__option === 'Some' && metadata.creators.value[0].verified).toEqual(true)
+  })
+
+  it('Counts every minted badge in the collection size', async () => {
+    await registerAndMint(Keypair.generate())
+    expect(await collectionSize()).toEqual(1)
+
+    await registerAndMint(Keypair.generate())
+    expect(await collectionSize()).toEqual(2)
+  })
+
+  it('Keeps verifying badges after handing over the collection authority', async () => {
+    await program.methods
+      .transferCollectionAuthority(name)
+      .accountsPartial({
+        creator: payer.publicKey,
+        eventAccount,
+        collectionMint,
+        metadata: metadataPda(collectionMint),
+        collectionAuthorityRecord,
+      })
+      .rpc()
+
+    const info = await provider.connection.getAccountInfo(metadataPda(collectionMint))
+    const [metadata] = getMetadataAccountDataSerializer().deserialize(info!.data)
+    expect(metadata.updateAuthority.toString()).toEqual(payer.publicKey.toBase58())
+
+    await registerAndMint(Keypair.generate())
+    expect(await collectionSize()).toEqual(3)
+  })
+
+  it('Airdrops badges to registrations one at a time and in a batch', async () => {
+    const event = await createEvent('airdrop', unixNow() - 60, unixNow() + 3600)
+    const single = Keypair.generate()
+    const batch = [Keypair.generate(), Keypair.generate()]
+    for (const attentee of [single, ...batch]) {
+      await register(attentee, event)
+    }
+    const sizeBefore = await collectionSize(event)
+
+    await airdropBadge(single, event)
+    expect(Number((await getAccount(provider.connection, badgeAccounts(single, event).destination)).amount)).toEqual(1)
+    expect((await program.account.eventRegistration.fetch(registrationPda(single, event))).attentenceNftMinted).toEqual(
+      true,
+    )
+    expect(await collectionSize(event)).toEqual(sizeBefore + 1)
+
+    await airdropBadges(batch, event)
+
+    for (const attentee of batch) {
+      const badge = await getAccount(provider.connection, badgeAccounts(attentee, event).destination)
+      expect(Number(badge.amount)).toEqual(1)
+      const registration = await program.account.eventRegistration.fetch(registrationPda(attentee, event))
+      expect(registration.attentenceNftMinted).toEqual(true)
+    }
+    expect(await collectionSize(event)).toEqual(sizeBefore + 3)
+    expect((await program.account.event.fetch(event.eventAccount)).mintedAttentees.toNumber()).toEqual(3)
+  })
+
+  it('Verifies the organizer as creator of airdropped badges only', async () => {
+    const event = await createEvent('creators', unixNow() - 60, unixNow() + 3600)
+    const creator = async (attentee: Keypair) => {
+      const info = await provider.connection.getAccountInfo(badgeAccounts(attentee, event).childNftMetadata)
+      const [metadata] = getMetadataAccountDataSerializer().deserialize(info!.data)
+      if (metadata.creators.__option !== 'Some') {
+        throw new Error('badge has no creators')
+      }
+      return metadata.creators.value[0]
+    }
+
+    // attentees mint without the organizer, the verified collection vouches for the badge
+    const selfServed = Keypair.generate()
+    await register(selfServed, event)
+    await checkIn(selfServed, event)
+    await mintBadge(selfServed, null, event)
+    expect((await creator(selfServed)).address.toString()).toEqual(payer.publicKey.toBase58())
+    expect((await creator(selfServed)).verified).toEqual(false)
+
+    const airdropped = Keypair.generate()
+    await register(airdropped, event)
+    await airdropBadge(airdropped, event)
+    expect((await creator(airdropped)).verified).toEqual(true)
+  })
+
+  it('Stays under the compute unit ceiling of each instruction', async () => {
+    const attentee = Keypair.generate()
+
+    expect(await computeUnits(await register(attentee))).toBeLessThanOrEqual(COMPUTE_UNIT_CEILINGS.registerEvent)
+    expect(await computeUnits(await checkIn(attentee))).toBeLessThanOrEqual(COMPUTE_UNIT_CEILINGS.checkIn)
+    expect(await computeUnits(await mintBadge(attentee))).toBeLessThanOrEqual(COMPUTE_UNIT_CEILINGS.mintNft)
+  })
+
+  it('Gates the badge of a multi-session event on the sessions attended', async () => {
+    const event = await createEvent('sessions', unixNow() - 60, unixNow() + 3600)
+    const sessionCodes = [1, 2, 3].map((seed) => Array(32).fill(seed))
+
+    for (const [id, code] of sessionCodes.entries()) {
+      await createSession(event, id, code)
+    }
+    await program.methods
+      .setBadgeRequirement(event.name, 2)
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+
+    const attentee = Keypair.generate()
+    await register(attentee, event)
+    await expect(checkIn(attentee, event)).rejects.toThrow('InvalidSessionId')
+
+    await checkInSession(attentee, event, 0, sessionCodes[0])
+    await expect(checkInSession(attentee, event, 0, sessionCodes[0])).rejects.toThrow('SessionAlreadyAttended')
+    await expect(mintBadge(attentee, null, event)).rejects.toThrow('NotEnoughSessionsAttended')
+
+    await checkInSession(attentee, event, 2, sessionCodes[2])
+    const registration = await program.account.eventRegistration.fetch(registrationPda(attentee, event))
+    expect(registration.sessionsAttended.toNumber()).toEqual(0b101)
+    expect((await program.account.event.fetch(event.eventAccount)).checkedInAttentees.toNumber()).toEqual(1)
+
+    await mintBadge(attentee, null, event)
+    expect(await collectionSize(event)).toEqual(1)
+  })
+
+  it('Records series attendance once per occurrence and resets the streak after a missed one', async () => {
+    const seriesName = `${name}-series`
+    const seriesAccount = pda([Buffer.from('series'), payer.publicKey.toBuffer(), Buffer.from(seriesName)])
+    const seriesMint = pda([Buffer.from('series_collection_mint'), payer.publicKey.toBuffer(), Buffer.from(seriesName)])
+
+    await program.methods
+      .initializeSeries(seriesName, 'series test', 'https://example.com/series.json', 'TLT', 0, [])
+      .accountsPartial({
+        payer: payer.publicKey,
+        seriesAccount,
+        collectionMint: seriesMint,
+        collectionTokenAccount: getAssociatedTokenAddressSync(seriesMint, payer.publicKey),
+        metadata: metadataPda(seriesMint),
+        masterEdition: masterEditionPda(seriesMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc()
+
+    const occurrences: EventKeys[] = []
+    for (const index of [0, 1, 2, 3]) {
+      const occurrence = eventKeys(`${name}-occ${index}`, seriesMint)
+      await program.methods
+        .createSeriesOccurrence(
+          occurrence.name,
+          `occurrence ${index}`,
+          attentanceCode,
+          new anchor.BN(unixNow() - 60),
+          new anchor.BN(unixNow() + 3600),
+          new anchor.BN(10),
+        )
+        .accountsPartial({
+          creator: payer.publicKey,
+          seriesAccount,
+          eventAccount: occurrence.eventAccount,
+          organizerAccount,
+        })
+        .rpc()
+      await createTier(occurrence, tierId, 'General')
+      occurrences.push(occurrence)
+    }
+
+    const attentee = Keypair.generate()
+    await fund(attentee.publicKey)
+    const attendanceAccount = pda([
+      Buffer.from('series_attendance'),
+      seriesAccount.toBuffer(),
+      attentee.publicKey.toBuffer(),
+    ])
+    const recordAttendance = (occurrence: EventKeys) =>
+      program.methods
+        .recordSeriesAttendance()
+        .accountsPartial({
+          attentee: attentee.publicKey,
+          seriesAccount,
+          eventAccount: occurrence.eventAccount,
+          registrationAccount: registrationPda(attentee, occurrence),
+          attendanceAccount,
+        })
+        .signers([attentee])
+        .rpc()
+
+    // occurrence 2 is missed
+    for (const occurrence of [occurrences[0], occurrences[1], occurrences[3]]) {
+      await register(attentee, occurrence)
+      await checkIn(attentee, occurrence)
+    }
+
+    await expect(recordAttendance(occurrences[0])).rejects.toThrow('NotAttended')
+    await mintBadge(attentee, null, occurrences[0])
+    await recordAttendance(occurrences[0])
+    await expect(recordAttendance(occurrences[0])).rejects.toThrow('SeriesAttendanceAlreadyRecorded')
+
+    await mintBadge(attentee, null, occurrences[1])
+    await recordAttendance(occurrences[1])
+    let attendance = await program.account.seriesAttendance.fetch(attendanceAccount)
+    expect(attendance.currentStreak).toEqual(2)
+    expect(attendance.longestStreak).toEqual(2)
+
+    await mintBadge(attentee, null, occurrences[3])
+    await recordAttendance(occurrences[3])
+    attendance = await program.account.seriesAttendance.fetch(attendanceAccount)
+    expect(attendance.occurrencesAttended).toEqual(3)
+    expect(attendance.lastOccurrence).toEqual(3)
+    expect(attendance.currentStreak).toEqual(1)
+    expect(attendance.longestStreak).toEqual(2)
+
+    // every occurrence mints into the series collection
+    expect(await collectionSize(occurrences[0])).toEqual(3)
+
+    // collections of a series belong to its creator, another organizer can reuse the name
+    const other = Keypair.generate()
+    await fund(other.publicKey, 10 * LAMPORTS_PER_SOL)
+    const otherMint = pda([Buffer.from('series_collection_mint'), other.publicKey.toBuffer(), Buffer.from(seriesName)])
+    await program.methods
+      .initializeSeries(seriesName, 'series test', 'https://example.com/series.json', 'TLT', 0, [])
+      .accountsPartial({
+        payer: other.publicKey,
+        seriesAccount: pda([Buffer.from('series'), other.publicKey.toBuffer(), Buffer.from(seriesName)]),
+        collectionMint: otherMint,
+        collectionTokenAccount: getAssociatedTokenAddressSync(otherMint, other.publicKey),
+        metadata: metadataPda(otherMint),
+        masterEdition: masterEditionPda(otherMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([other])
+      .rpc()
+    expect(await provider.connection.getAccountInfo(otherMint)).not.toBeNull()
+  })
+
+  it('Cranks stale registrations and expired events closed for a bounty', async () => {
+    const event = await createEvent('crank', unixNow() - 60, unixNow() + 3600)
+    const attentees = [Keypair.generate(), Keypair.generate()]
+    const cranker = Keypair.generate()
+    await fund(cranker.publicKey)
+
+    for (const attentee of attentees) {
+      await register(attentee, event)
+    }
+    await checkIn(attentees[0], event)
+
+    await expect(closeStaleRegistration(cranker, attentees[0], event)).rejects.toThrow('CleanupNotAllowedYet')
+    await expect(closeExpiredEvent(cranker, event)).rejects.toThrow('CleanupNotAllowedYet')
+
+    const ended = unixNow() - CLEANUP_GRACE_PERIOD - 60
+    await editEvent(event, ended - 3600, ended)
+
+    await expect(closeEvent(event)).rejects.toThrow('EventNotEmpty')
+    await expect(closeExpiredEvent(cranker, event)).rejects.toThrow('EventNotEmpty')
+
+    const payerBalance = await provider.connection.getBalance(payer.publicKey)
+    await closeStaleRegistration(cranker, attentees[0], event)
+    await closeStaleRegistration(cranker, attentees[1], event)
+    expect(await program.account.eventRegistration.fetchNullable(registrationPda(attentees[0], event))).toBeNull()
+    // registration rent goes back to the organizer wallet that paid it
+    expect(await provider.connection.getBalance(payer.publicKey)).toBeGreaterThan(payerBalance)
+
+    const organizerBefore = await program.account.organizerProfile.fetch(organizerAccount)
+    const crankerBalance = await provider.connection.getBalance(cranker.publicKey)
+    await closeExpiredEvent(cranker, event)
+
+    expect(await provider.connection.getBalance(cranker.publicKey)).toEqual(crankerBalance + CLEANUP_BOUNTY_LAMPORTS)
+    expect(await program.account.event.fetchNullable(event.eventAccount)).toBeNull()
+
+    const organizer = await program.account.organizerProfile.fetch(organizerAccount)
+    expect(organizer.eventsCompleted.toNumber()).toEqual(organizerBefore.eventsCompleted.toNumber() + 1)
+    expect(organizer.totalRegistrations.toNumber()).toEqual(organizerBefore.totalRegistrations.toNumber() + 2)
+    expect(organizer.totalCheckedIn.toNumber()).toEqual(organizerBefore.totalCheckedIn.toNumber() + 1)
+  })
+
+  it('Returns deposits to attentees who showed up and forfeits the rest after the deadline', async () => {
+    // created after it ended so the claim deadline can be close, then
+    // reopened for the check-in
+    const event = await createEvent('deposit', unixNow() - 120, unixNow() - 60)
+    const deposit = await provider.connection.getMinimumBalanceForRentExemption(0)
+    const beneficiary = Keypair.generate().publicKey
+    const deadline = unixNow() + 45
+
+    await program.methods
+      .setEventDeposit(event.name, new anchor.BN(deposit), beneficiary, new anchor.BN(deadline))
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+
+    const [attended, noShow] = [Keypair.generate(), Keypair.generate()]
+    const cranker = Keypair.generate()
+    for (const attentee of [attended, noShow, cranker]) {
+      await fund(attentee.publicKey)
+    }
+    await register(attended, event)
+    await register(noShow, event)
+    expect(await provider.connection.getBalance(event.vault)).toEqual(2 * deposit)
+
+    const reclaimDeposit = (attentee: Keypair) =>
+      program.methods
+        .reclaimDeposit()
+        .accountsPartial({
+          attentee: attentee.publicKey,
+          eventAccount: event.eventAccount,
+          registrationAccount: registrationPda(attentee, event),
+          vault: event.vault,
+        })
+        .signers([attentee])
+        .rpc()
+    const claimForfeitedDeposits = () =>
+      program.methods
+        .claimForfeitedDeposits()
+        .accountsPartial({
+          creator: payer.publicKey,
+          eventAccount: event.eventAccount,
+          beneficiary,
+          vault: event.vault,
+        })
+        .rpc()
+
+    await editEvent(event, unixNow() - 120, unixNow() + 3600)
+    await checkIn(attended, event)
+    await expect(reclaimDeposit(attended)).rejects.toThrow('DepositClaimClosed')
+
+    // past the grace period, but the crank still waits for the claim deadline
+    const ended = unixNow() - CLEANUP_GRACE_PERIOD - 60
+    await editEvent(event, ended - 3600, ended)
+    await expect(closeStaleRegistration(cranker, noShow, event)).rejects.toThrow('CleanupNotAllowedYet')
+
+    await expect(reclaimDeposit(noShow)).rejects.toThrow('NotAttended')
+    const balance = await provider.connection.getBalance(attended.publicKey)
+    await reclaimDeposit(attended)
+    expect(await provider.connection.getBalance(attended.publicKey)).toEqual(balance + deposit)
+    await expect(reclaimDeposit(attended)).rejects.toThrow('NoDepositToClaim')
+    await expect(claimForfeitedDeposits()).rejects.toThrow('DepositClaimClosed')
+
+    // cancelling after the start keeps the deposit in the vault
+    await cancelRegistration(attended, event)
+    await cancelRegistration(noShow, event)
+    await expect(closeEvent(event)).rejects.toThrow('VaultNotEmpty')
+
+    await sleep(deadline - unixNow() + 2)
+    await claimForfeitedDeposits()
+    expect(await provider.connection.getBalance(beneficiary)).toEqual(deposit)
+    expect(await provider.connection.getBalance(event.vault)).toEqual(0)
+
+    await closeEvent(event)
+    expect(await program.account.event.fetchNullable(event.eventAccount)).toBeNull()
+  }, 120_000)
+
+  it('Checks in at a geofenced event only with a fresh attestation from inside the venue', async () => {
+    const event = await createEvent('geofence', unixNow() - 60, unixNow() + 3600)
+    const authority = Keypair.generate()
+    const attentee = Keypair.generate()
+    await register(attentee, event)
+    await program.methods
+      .setGeofence(event.name, 'u4pru', authority.publicKey)
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+
+    const attestedCheckIn = (signer: Keypair, geohash: string, timestamp: number) => {
+      const observedAt = Buffer.alloc(8)
+      observedAt.writeBigInt64LE(BigInt(timestamp))
+      const message = Buffer.concat([
+        attentee.publicKey.toBuffer(),
+        event.eventAccount.toBuffer(),
+        Buffer.from(geohash),
+        observedAt,
+      ])
+
+      return program.methods
+        .checkInWithAttestation()
+        .accountsPartial({
+          attentee: attentee.publicKey,
+          eventAccount: event.eventAccount,
+          registrationAccount: registrationPda(attentee, event),
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message })])
+        .signers([attentee])
+        .rpc()
+    }
+
+    await expect(checkIn(attentee, event)).rejects.toThrow('AttestationRequired')
+    await expect(attestedCheckIn(Keypair.generate(), 'u4pruy', unixNow() - 30)).rejects.toThrow('InvalidAttestation')
+    await expect(attestedCheckIn(authority, 'u4pruy', unixNow() - 10 * 60)).rejects.toThrow('AttestationExpired')
+    await expect(attestedCheckIn(authority, 'gcpvj0', unixNow() - 30)).rejects.toThrow('OutsideGeofence')
+    expect((await program.account.eventRegistration.fetch(registrationPda(attentee, event))).attented).toEqual(false)
+
+    await attestedCheckIn(authority, 'u4pruy', unixNow() - 30)
+    expect((await program.account.eventRegistration.fetch(registrationPda(attentee, event))).attented).toEqual(true)
+  })
+
+  it('Accepts rotating codes of the current window and moves the checkpoint forward', async () => {
+    const start = unixNow() + 5
+    const event = await createEvent('rotation', start, start + 600)
+    const period = 10
+    const sha256 = (value: Buffer) => createHash('sha256').update(value).digest()
+    const hashChain = (value: Buffer, steps: number) =>
+      Array.from({ length: steps }).reduce<Buffer>((hashed) => sha256(hashed), value)
+
+    // the code of window w hashes to the commitment in w steps
+    const chainLength = 64
+    const seed = Buffer.from(Keypair.generate().secretKey.subarray(0, 32))
+    const codeOf = (window: number) => [...hashChain(seed, chainLength - window)]
+    const setCodeRotation = (eventKeys: EventKeys) =>
+      program.methods
+        .setCodeRotation(eventKeys.name, [...hashChain(seed, chainLength)], period)
+        .accountsPartial({ creator: payer.publicKey, eventAccount: eventKeys.eventAccount })
+        .rpc()
+
+    // an hour of 10 second windows would take too many hashes to verify
+    const long = await createEvent('rotation-long', unixNow() + 60, unixNow() + 3600)
+    await expect(setCodeRotation(long)).rejects.toThrow('InvalidCodeRotation')
+
+    await setCodeRotation(event)
+    const onTime = Keypair.generate()
+    const late = Keypair.generate()
+    await register(onTime, event)
+    await register(late, event)
+    await sleep(start + 3 * period + 2 - unixNow())
+
+    // the previous window stays valid, whatever second the validator is at
+    const window = Math.floor((unixNow() - start) / period)
+    await checkIn(onTime, event, codeOf(window))
+    expect((await program.account.eventRegistration.fetch(registrationPda(onTime, event))).attented).toEqual(true)
+    const checkpoint = await program.account.event.fetch(event.eventAccount)
+    expect(checkpoint.codeCheckpointWindow).toEqual(window)
+    expect(checkpoint.attentanceCode).toEqual(codeOf(window))
+
+    await checkIn(late, event, codeOf(window - 2))
+    const stale = await program.account.eventRegistration.fetch(registrationPda(late, event))
+    expect(stale.attented).toEqual(false)
+    expect(stale.failedCodeAttempts).toEqual(1)
+  }, 60_000)
+
+  it('Mints badges only while the event runs', async () => {
+    const event = await createEvent('not-started', unixNow() + 600, unixNow() + 3600)
+    const early = Keypair.generate()
+    await register(early, event)
+
+    await expect(mintBadge(early, null, event)).rejects.toThrow('NotMinitingTime')
+  })
+
+  it('Records answers to the registration questions and keeps a profile across events', async () => {
+    const event = await createEvent('questions', unixNow() - 60, unixNow() + 3600)
+    const questionsAccount = pda([Buffer.from('questions'), event.eventAccount.toBuffer()])
+    const setQuestions = () =>
+      program.methods
+        .setRegistrationQuestions(event.name, [
+          { kind: { choice: {} }, prompt: 'T-shirt size: S, M or L', choices: 3, required: true },
+          { kind: { text: {} }, prompt: 'Dietary requirements', choices: 0, required: false },
+        ])
+        .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount, questionsAccount })
+        .rpc()
+    type Answers = Parameters<typeof program.methods.registerEvent>[1]
+    const registerWith = (attentee: Keypair, answers: Answers) =>
+      program.methods
+        .registerEvent(tierId, answers)
+        .accountsPartial({
+          attentee: attentee.publicKey,
+          payer: payer.publicKey,
+          eventAccount: event.eventAccount,
+          creator: payer.publicKey,
+          vault: event.vault,
+          tierAccount: event.tierAccount,
+          registrationAccount: registrationPda(attentee, event),
+          questionsAccount,
+          waitlistEntry: waitlistPda(attentee, event),
+          waitlistPayer: null,
+          treasury,
+        })
+        .signers([attentee])
+        .rpc()
+
+    await setQuestions()
+    const attentee = Keypair.generate()
+    const dietary = createHash('sha256').update('vegetarian').digest()
+
+    await expect(registerWith(attentee, [{ skipped: {} }, { skipped: {} }])).rejects.toThrow('InvalidAnswers')
+    await expect(registerWith(attentee, [{ choice: { 0: 3 } }, { skipped: {} }])).rejects.toThrow('InvalidAnswers')
+    await expect(registerWith(attentee, [{ choice: { 0: 1 } }])).rejects.toThrow('InvalidAnswers')
+    await expect(registerWith(attentee, [{ number: { 0: new anchor.BN(1) } }, { skipped: {} }])).rejects.toThrow(
+      'InvalidAnswers',
+    )
+
+    await registerWith(attentee, [{ choice: { 0: 1 } }, { textHash: { 0: [...dietary] } }])
+    const registration = await program.account.eventRegistration.fetch(registrationPda(attentee, event))
+    expect(registration.answers).toEqual([{ choice: { 0: 1 } }, { textHash: { 0: [...dietary] } }])
+    // answers stay comparable once someone registered
+    await expect(setQuestions()).rejects.toThrow('QuestionsLocked')
+
+    await fund(attentee.publicKey)
+    const profileAccount = pda([Buffer.from('profile'), attentee.publicKey.toBuffer()])
+    const updateProfile = (displayName: string) =>
+      program.methods
+        .updateProfile(displayName, [...createHash('sha256').update('ada@example.com').digest()], '@ada', 0b101)
+        .accountsPartial({ attentee: attentee.publicKey, profileAccount })
+        .signers([attentee])
+        .rpc()
+
+    await expect(updateProfile('a'.repeat(33))).rejects.toThrow('InvalidProfile')
+    await updateProfile('Ada')
+    await updateProfile('Ada L.')
+    const profile = await program.account.attendeeProfile.fetch(profileAccount)
+    expect(profile.attentee.toBase58()).toEqual(attentee.publicKey.toBase58())
+    expect(profile.displayName).toEqual('Ada L.')
+    expect(profile.socialHandle).toEqual('@ada')
+    expect(profile.preferences).toEqual(0b101)
+
+    await program.methods
+      .closeProfile()
+      .accountsPartial({ attentee: attentee.publicKey, profileAccount })
+      .signers([attentee])
+      .rpc()
+    expect(await program.account.attendeeProfile.fetchNullable(profileAccount)).toBeNull()
+  })
+
+  it('Tracks hosted and cancelled events on the organizer profile', async () => {
+    const cancelEvent = (event: EventKeys) =>
+      program.methods
+        .cancelEvent(event.name)
+        .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount, organizerAccount })
+        .rpc()
+    const organizerBefore = await program.account.organizerProfile.fetch(organizerAccount)
+
+    const event = await createEvent('cancelled', unixNow() - 60, unixNow() + 3600)
+    const ended = await createEvent('ended', unixNow() - 120, unixNow() - 60)
+    const hosted = await program.account.organizerProfile.fetch(organizerAccount)
+    expect(hosted.organizer.toBase58()).toEqual(payer.publicKey.toBase58())
+    expect(hosted.eventsHosted.toNumber()).toEqual(organizerBefore.eventsHosted.toNumber() + 2)
+
+    const attentees = [Keypair.generate(), Keypair.generate()]
+    for (const attentee of attentees) {
+      await register(attentee, event)
+    }
+    await checkIn(attentees[0], event)
+
+    await expect(cancelEvent(ended)).rejects.toThrow('EventAlreadyEnded')
+    await cancelEvent(event)
+    await expect(cancelEvent(event)).rejects.toThrow('EventCancelled')
+
+    const cancelled = await program.account.organizerProfile.fetch(organizerAccount)
+    expect(cancelled.eventsCancelled.toNumber()).toEqual(organizerBefore.eventsCancelled.toNumber() + 1)
+    expect(cancelled.eventsCompleted.toNumber()).toEqual(organizerBefore.eventsCompleted.toNumber())
+    expect(cancelled.totalRegistrations.toNumber()).toEqual(organizerBefore.totalRegistrations.toNumber() + 2)
+    expect(cancelled.totalCheckedIn.toNumber()).toEqual(organizerBefore.totalCheckedIn.toNumber() + 1)
+
+    // closing a cancelled event does not count it a second time
+    for (const attentee of attentees) {
+      await cancelRegistration(attentee, event)
+    }
+    await closeEvent(event)
+    const closed = await program.account.organizerProfile.fetch(organizerAccount)
+    expect(closed.eventsCancelled.toNumber()).toEqual(cancelled.eventsCancelled.toNumber())
+    expect(closed.eventsCompleted.toNumber()).toEqual(cancelled.eventsCompleted.toNumber())
+    expect(closed.totalRegistrations.toNumber()).toEqual(cancelled.totalRegistrations.toNumber())
+  })
+
+  it('Pays sponsor rewards to attentees who showed up and refunds the rest to the sponsor', async () => {
+    // created after it ended so the refund deadline can be close, then
+    // reopened for funding and the check-in
+    const start = unixNow() - 120
+    const event = await createEvent('sponsored', start, unixNow() - 60)
+    const closing = await createEvent('sponsored-closed', unixNow() - 60, unixNow() + 3600)
+    const sponsor = Keypair.generate()
+    await fund(sponsor.publicKey)
+    const mint = await createMint(provider.connection, payer.payer, payer.publicKey, null, 0)
+    const sponsorTokens = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer.payer,
+      mint,
+      sponsor.publicKey,
+    )
+    await mintTo(provider.connection, payer.payer, mint, sponsorTokens.address, payer.payer, 1_000)
+    const sponsorBalance = async () => Number((await getAccount(provider.connection, sponsorTokens.address)).amount)
+
+    const poolKeys = (keys: EventKeys) => {
+      const sponsorPool = pda([
+        Buffer.from('sponsor_pool'),
+        keys.eventAccount.toBuffer(),
+        sponsor.publicKey.toBuffer(),
+        mint.toBuffer(),
+      ])
+      return { sponsorPool, poolTokenAccount: getAssociatedTokenAddressSync(mint, sponsorPool, true) }
+    }
+    const createPool = (keys: EventKeys, refundDeadline: number) =>
+      program.methods
+        .createSponsorPool(new anchor.BN(100), new anchor.BN(refundDeadline))
+        .accountsPartial({
+          sponsor: sponsor.publicKey,
+          eventAccount: keys.eventAccount,
+          mint,
+          ...poolKeys(keys),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([sponsor])
+        .rpc()
+    const fundPool = (keys: EventKeys, amount: number) =>
+      program.methods
+        .fundSponsorPool(new anchor.BN(amount))
+        .accountsPartial({
+          funder: sponsor.publicKey,
+          eventAccount: keys.eventAccount,
+          mint,
+          config,
+          funderTokenAccount: sponsorTokens.address,
+          ...poolKeys(keys),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([sponsor])
+        .rpc()
+    const claim = (attentee: Keypair) =>
+      program.methods
+        .claimSponsorReward()
+        .accountsPartial({
+          attentee: attentee.publicKey,
+          payer: payer.publicKey,
+          eventAccount: event.eventAccount,
+          registrationAccount: registrationPda(attentee, event),
+          sponsorClaim: pda([
+            Buffer.from('sponsor_claim'),
+            poolKeys(event).sponsorPool.toBuffer(),
+            registrationPda(attentee, event).toBuffer(),
+          ]),
+          mint,
+          ...poolKeys(event),
+          attenteeTokenAccount: getAssociatedTokenAddressSync(mint, attentee.publicKey),
+          config,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([attentee])
+        .rpc()
+    const reclaim = (keys: EventKeys) =>
+      program.methods
+        .reclaimSponsorPool()
+        .accountsPartial({
+          sponsor: sponsor.publicKey,
+          eventAccount: keys.eventAccount,
+          mint,
+          ...poolKeys(keys),
+          sponsorTokenAccount: sponsorTokens.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([sponsor])
+        .rpc()
+
+    const refundDeadline = unixNow() + 45
+    await createPool(event, refundDeadline)
+    await createPool(closing, unixNow() + 7200)
+    // the crank leaves registrations alone until claims are over
+    expect((await program.account.event.fetch(event.eventAccount)).sponsorRefundDeadline.toNumber()).toEqual(
+      refundDeadline,
+    )
+
+    await editEvent(event, start, unixNow() + 3600)
+    await fundPool(event, 500)
+    await fundPool(closing, 200)
+    expect(await sponsorBalance()).toEqual(300)
+
+    const showedUp = Keypair.generate()
+    const noShow = Keypair.generate()
+    await register(showedUp, event)
+    await register(noShow, event)
+    await checkIn(showedUp, event)
+    await expect(claim(showedUp)).rejects.toThrow('SponsorClaimClosed')
+
+    await program.methods
+      .setEventPaused(event.name, true)
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+    await expect(fundPool(event, 1)).rejects.toThrow('EventPaused')
+    await expect(claim(showedUp)).rejects.toThrow('EventPaused')
+    await program.methods
+      .setEventPaused(event.name, false)
+      .accountsPartial({ creator: payer.publicKey, eventAccount: event.eventAccount })
+      .rpc()
+
+    await editEvent(event, start, unixNow() - 1)
+    await expect(fundPool(event, 1)).rejects.toThrow('SponsorPoolClosed')
+    await expect(reclaim(event)).rejects.toThrow('SponsorRefundNotOpen')
+    await expect(claim(noShow)).rejects.toThrow('NotAttended')
+    await claim(showedUp)
+    const reward = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, showedUp.publicKey))
+    expect(Number(reward.amount)).toEqual(100)
+    // the claim account is already taken
+    await expect(claim(showedUp)).rejects.toThrow()
+
+    // nobody can claim from a closed event, so its pool is refunded right away
+    await closeEvent(closing)
+    await reclaim(closing)
+    expect(await sponsorBalance()).toEqual(500)
+
+    await sleep(refundDeadline - unixNow() + 2)
+    await reclaim(event)
+    expect(await sponsorBalance()).toEqual(900)
+    expect(await program.account.sponsorPool.fetchNullable(poolKeys(event).sponsorPool)).toBeNull()
+    expect(await provider.connection.getAccountInfo(poolKeys(event).poolTokenAccount)).toBeNull()
+  }, 120_000)
+
+  it('Sends the platform fee of paid tickets to the treasury', async () => {
+    const price = LAMPORTS_PER_SOL
+    const paidTierAccount = pda([Buffer.from('tier'), eventAccount.toBuffer(), Buffer.from([paidTierId])])
+
+    await program.methods
+      .createTicketTier(
+        paidTierId,
+        'Paid',
+        new anchor.BN(price),
+        new anchor.BN(10),
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 3600),
+        'https://example.com/paid.json',
+      )
+      .accountsPartial({ creator: payer.publicKey, eventAccount, tierAccount: paidTierAccount })
+      .rpc()
+
+    const attentee = Keypair.generate()
+    const airdrop = await provider.connection.requestAirdrop(attentee.publicKey, 2 * LAMPORTS_PER_SOL)
+    await provider.connection.confirmTransaction(airdrop)
+
+    await program.methods
+      .registerEvent(paidTierId, [])
+      .accountsPartial({
+        attentee: attentee.publicKey,
+        payer: payer.publicKey,
+        eventAccount,
+        creator: payer.publicKey,
+        vault,
+        tierAccount: paidTierAccount,
+        registrationAccount: pda([Buffer.from('attentee'), eventAccount.toBuffer(), attentee.publicKey.toBuffer()]),
+        questionsAccount: null,
+        waitlistEntry: waitlistPda(attentee),
+        waitlistPayer: null,
+        treasury,
+      })
+      .signers([attentee])
+      .rpc()
+
+    expect(await provider.connection.getBalance(treasury)).toEqual((price * feeBasisPoints) / 10_000)
+  })
+
+  it('Sells each ticket tier within its own capacity, window and price', async () => {
+    const event = await createEvent('tiers', unixNow() - 60, unixNow() + 3600)
+    const price = LAMPORTS_PER_SOL / 10
+
+    await createTier(event, 1, 'VIP', { price, capacity: 1, uri: 'https://example.com/vip.json' })
+    await createTier(event, 2, 'Late', { start: unixNow() + 3600, end: unixNow() + 7200 })
+
+    const vip = await program.account.ticketTier.fetch(tierPda(event.eventAccount, 1))
+    expect(vip.name).toEqual('VIP')
+    expect(vip.price.toNumber()).toEqual(price)
+    expect(vip.capacity.toNumber()).toEqual(1)
+    expect(vip.uri).toEqual('https://example.com/vip.json')
+
+    const first = Keypair.generate()
+    await fund(first.publicKey)
+    const balanceBefore = await provider.connection.getBalance(first.publicKey)
+    await register(first, event, 1)
+
+    // the attentee pays the ticket, the organizer wallet pays rent and fees
+    expect(balanceBefore - (await provider.connection.getBalance(first.publicKey))).toEqual(price)
+    const vipAfter = await program.account.ticketTier.fetch(tierPda(event.eventAccount, 1))
+    expect(vipAfter.registeredAttentees.toNumber()).toEqual(1)
+
+    const second = Keypair.generate()
+    await fund(second.publicKey)
+    await expect(register(second, event, 1)).rejects.toThrow('TierSoldOut')
+    await expect(register(second, event, 2)).rejects.toThrow('TierRegistrationClosed')
+    await register(second, event)
+
+    await expect(createTier(event, 3, 'x'.repeat(17))).rejects.toThrow('InvalidTierMetadata')
+    await expect(createTier(event, 3, 'Long uri', { uri: 'x'.repeat(65) })).rejects.toThrow('InvalidTierMetadata')
+    await expect(createTier(event, 3, 'Empty', { start: unixNow(), end: unixNow() })).rejects.toThrow(
+      'InvalidTierWindow',
+    )
+  })
+
+  it('Stops registrations, mints and payouts of a paused event', async () => {
+    const checkedIn = Keypair.generate()
+    await register(checkedIn)
+    await checkIn(checkedIn)
+
+    await program.methods.setEventPaused(name, true).accountsPartial({ creator: payer.publicKey, eventAccount }).rpc()
+
+    await expect(register(Keypair.generate())).rejects.toThrow('EventPaused')
+    await expect(mintBadge(checkedIn)).rejects.toThrow('EventPaused')
+    await expect(airdropBadge(checkedIn)).rejects.toThrow('EventPaused')
+    await expect(airdropBadges([checkedIn])).rejects.toThrow('EventPaused')
+    await expect(walkInMint(Keypair.generate())).rejects.toThrow('EventPaused')
+    await expect(claimForfeitedDeposits()).rejects.toThrow('EventPaused')
+    await expect(closeExpiredEvent(Keypair.generate(), main)).rejects.toThrow('EventPaused')
+
+    // refunds and cancellations stay open
+    const cancelling = Keypair.generate()
+    await program.methods.setEventPaused(name, false).accountsPartial({ creator: payer.publicKey, eventAccount }).rpc()
+    await register(cancelling)
+    await program.methods.setEventPaused(name, true).accountsPartial({ creator: payer.publicKey, eventAccount }).rpc()
+
+    await program.methods
+      .cancelRegistration()
+      .accountsPartial({
+        attentee: cancelling.publicKey,
+        eventAccount,
+        registrationAccount: registrationPda(cancelling),
+        payer: payer.publicKey,
+        tierAccount,
+        vault,
+      })
+      .signers([cancelling])
+      .rpc()
+    expect(await program.account.eventRegistration.fetchNullable(registrationPda(cancelling))).toBeNull()
+
+    await program.methods.setEventPaused(name, false).accountsPartial({ creator: payer.publicKey, eventAccount }).rpc()
+    await mintBadge(checkedIn)
+  })
+
+  it('Stops event creation, registrations, mints and payouts while the program is paused', async () => {
+    const checkedIn = Keypair.generate()
+    await register(checkedIn)
+    await checkIn(checkedIn)
+
+    await program.methods.setProgramPaused(true).accountsPartial({ admin: payer.publicKey, config }).rpc()
+
+    const pausedName = `paused-${Date.now() % 1_000_000}`
+    const pausedCollectionMint = pda([Buffer.from('collection_mint'), Buffer.from(pausedName)])
+    await expect(
+      program.methods
+        .initializeEvent(
+          pausedName,
+          'paused',
+          'https://example.com/event.json',
+          attentanceCode,
+          new anchor.BN(now - 60),
+          new anchor.BN(now + 3600),
+          new anchor.BN(10),
+          pausedCollectionMint,
+          'TLT',
+          0,
+          [{ address: payer.publicKey, share: 100 }],
+        )
+        .accountsPartial({
+          payer: payer.publicKey,
+          eventAccount: pda([Buffer.from('event'), payer.publicKey.toBuffer(), Buffer.from(pausedName)]),
+          collectionMint: pausedCollectionMint,
+          collectionTokenAccount: getAssociatedTokenAddressSync(pausedCollectionMint, payer.publicKey),
+          metadata: metadataPda(pausedCollectionMint),
+          masterEdition: masterEditionPda(pausedCollectionMint),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .rpc(),
+    ).rejects.toThrow('ProgramPaused')
+    await expect(register(Keypair.generate())).rejects.toThrow('ProgramPaused')
+    await expect(mintBadge(checkedIn)).rejects.toThrow('ProgramPaused')
+    await expect(airdropBadge(checkedIn)).rejects.toThrow('ProgramPaused')
+    await expect(claimForfeitedDeposits()).rejects.toThrow('ProgramPaused')
+    await expect(airdropBadges([checkedIn])).rejects.toThrow('ProgramPaused')
+    await expect(walkInMint(Keypair.generate())).rejects.toThrow('ProgramPaused')
+    await expect(closeExpiredEvent(Keypair.generate(), main)).rejects.toThrow('ProgramPaused')
+
+    await program.methods.setProgramPaused(false).accountsPartial({ admin: payer.publicKey, config }).rpc()
+    await mintBadge(checkedIn)
+  })
+
+  it('Registers, checks in and mints a walk-in in one instruction', async () => {
+    const walkIn = Keypair.generate()
+    const registeredBefore = (await program.account.event.fetch(eventAccount)).registeredAttentees
+
+    await expect(walkInMint(walkIn)).rejects.toThrow('WalkInNotAllowed')
+
+    await program.methods.setWalkInTier(name, tierId).accountsPartial({ creator: payer.publicKey, eventAccount }).rpc()
+
+    await walkInMint(walkIn)
+
+    const registration = await program.account.eventRegistration.fetch(registrationPda(walkIn))
+    expect(registration.attented).toEqual(true)
+    expect(registration.attentenceNftMinted).toEqual(true)
+    const registeredAfter = (await program.account.event.fetch(eventAccount)).registeredAttentees
+    expect(registeredAfter.toNumber()).toEqual(registeredBefore.toNumber() + 1)
+
+    const badge = await getAccount(provider.connection, badgeAccounts(walkIn).destination)
+    expect(Number(badge.amount)).toEqual(1)
+  })
+
+  it('Spends each claim code once', async () => {
+    const sha256 = (...parts: Buffer[]) => createHash('sha256').update(Buffer.concat(parts)).digest()
+    const codes = [Buffer.alloc(32, 1), Buffer.alloc(32, 2)]
+    const leaves = codes.map((code, index) => {
+      const indexBytes = Buffer.alloc(4)
+      indexBytes.writeUInt32LE(index)
+      return sha256(Buffer.from('claim_code'), indexBytes, code)
+    })
+    const root = sha256(...[...leaves].sort(Buffer.compare))
+    const claimCode = (index: number, code = codes[index]): ClaimCode => ({
+      index,
+      code: Array.from(code),
+      proof: [Array.from(leaves[1 - index])],
+    })
+
+    await program.methods
+      .setClaimCodes(name, Array.from(root), codes.length)
+      .accountsPartial({ creator: payer.publicKey, eventAccount, claimCodesAccount })
+      .rpc()
+
+    const first = Keypair.generate()
+    const second = Keypair.generate()
+    await register(first)
+    await register(second)
+
+    await mintBadge(first, claimCode(0))
+    await expect(mintBadge(second, claimCode(0))).rejects.toThrow('ClaimCodeSpent')
+    await expect(mintBadge(second)).rejects.toThrow('ClaimCodeRequired')
+    await expect(mintBadge(second, claimCode(1, codes[0]))).rejects.toThrow('InvalidClaimCode')
+    await mintBadge(second, claimCode(1))
+
+    expect((await program.account.eventRegistration.fetch(registrationPda(second))).attented).toEqual(true)
+
+    // outside the venue of a geofenced event, a code alone is not enough
+    const geofenced = await createEvent('claim-geofence', unixNow() - 60, unixNow() + 3600)
+    await program.methods
+      .setClaimCodes(geofenced.name, Array.from(root), codes.length)
+      .accountsPartial({
+        creator: payer.publicKey,
+        eventAccount: geofenced.eventAccount,
+        claimCodesAccount: pda([Buffer.from('claim_codes'), geofenced.eventAccount.toBuffer()]),
+      })
+      .rpc()
+    await program.methods
+      .setGeofence(geofenced.name, 'u4pru', Keypair.generate().publicKey)
+      .accountsPartial({ creator: payer.publicKey, eventAccount: geofenced.eventAccount })
+      .rpc()
+    const remote = Keypair.generate()
+    await register(remote, geofenced)
+    await expect(mintBadge(remote, claimCode(0), geofenced)).rejects.toThrow('AttestationRequired')
+  })
+
+  it('Closes registration once capacity is edited below the count and fills the waitlist', async () => {
+    const registered = (await program.account.event.fetch(eventAccount)).registeredAttentees
+
+    await program.methods
+      .editEvent(name, attentanceCode, new anchor.BN(now - 60), new anchor.BN(now + 3600), registered.subn(1))
+      .accountsPartial({ creator: payer.publicKey, eventAccount })
+      .rpc()
+
+    const waiting = Keypair.generate()
+    await expect(register(waiting)).rejects.toThrow('RegistrationCompleted')
+
+    await joinWaitlist(waiting)
+
+    const stats = await program.methods.eventStats().accounts({ eventAccount }).view()
+    const event = await program.account.event.fetch(eventAccount)
+    expect(stats.registered.toNumber()).toEqual(registered.toNumber())
+    expect(stats.minted.toNumber()).toEqual(event.mintedAttentees.toNumber())
+    expect(stats.cancelled.toNumber()).toEqual(1)
+    expect(stats.waitlisted.toNumber()).toEqual(1)
+
+    await program.methods
+      .leaveWaitlist()
+      .accountsPartial({
+        attentee: waiting.publicKey,
+        eventAccount,
+        waitlistEntry: waitlistPda(waiting),
+        payer: payer.publicKey,
+      })
+      .signers([waiting])
+      .rpc()
+    expect((await program.account.event.fetch(eventAccount)).waitlistedAttentees.toNumber()).toEqual(0)
+
+    // registered attentees have no place on the waitlist
+    const full = await createEvent('waitlist', unixNow() - 60, unixNow() + 3600, 1)
+    const seated = Keypair.generate()
+    await register(seated, full)
+    await expect(joinWaitlist(seated, full)).rejects.toThrow('AlreadyRegistered')
+
+    // a waitlisted wallet that gets a seat comes off the waitlist, even when
+    // another sponsor paid for its entry
+    const promoted = Keypair.generate()
+    const sponsor = Keypair.generate()
+    await fund(sponsor.publicKey)
+    await joinWaitlist(promoted, full, sponsor)
+
+    const [start, end] = [new anchor.BN(unixNow() - 60), new anchor.BN(unixNow() + 3600)]
+    await program.methods
+      .editEvent(full.name, attentanceCode, start, end, new anchor.BN(2))
+      .accountsPartial({ creator: payer.publicKey, eventAccount: full.eventAccount })
+      .rpc()
+    await expect(register(promoted, full)).rejects.toThrow('WaitlistPayerMismatch')
+
+    const sponsorBalance = await provider.connection.getBalance(sponsor.publicKey)
+    const entryRent = await provider.connection.getBalance(waitlistPda(promoted, full))
+    await register(promoted, full, tierId, sponsor.publicKey)
+
+    expect((await program.account.event.fetch(full.eventAccount)).waitlistedAttentees.toNumber()).toEqual(0)
+    expect(await program.account.waitlistEntry.fetchNullable(waitlistPda(promoted, full))).toBeNull()
+    expect(await provider.connection.getBalance(sponsor.publicKey)).toEqual(sponsorBalance + entryRent)
+  })
+
+  // waits out the lockout in real time, so it runs last
+  it('Locks a registration out after repeated wrong codes until the lockout passes', async () => {
+    const CODE_LOCKOUT_PERIOD = 10 * 60
+    const event = await createEvent('lockout', unixNow() - 60, unixNow() + 3600)
+    const guesser = Keypair.generate()
+    await register(guesser, event)
+    const registration = () => program.account.eventRegistration.fetch(registrationPda(guesser, event))
+    const wrongCode = Array(32).fill(8)
+
+    for (let attempt = 1; attempt < 5; attempt++) {
+      await checkIn(guesser, event, wrongCode)
+      expect((await registration()).failedCodeAttempts).toEqual(attempt)
+    }
+    await checkIn(guesser, event, wrongCode)
+
+    const locked = await registration()
+    expect(locked.failedCodeAttempts).toEqual(0)
+    expect(locked.codeLockedUntil.toNumber()).toBeGreaterThan(unixNow() + CODE_LOCKOUT_PERIOD - 30)
+    await expect(checkIn(guesser, event)).rejects.toThrow('CodeLockedOut')
+
+    await sleep(locked.codeLockedUntil.toNumber() - unixNow() + 2)
+    await checkIn(guesser, event)
+    expect((await registration()).attented).toEqual(true)
+  }, 11 * 60 * 1000)
+})